@@ -0,0 +1,195 @@
+use crate::models::repository::DbError;
+use crate::utils::template::render;
+use crate::utils::url_safety::UnsafeUrl;
+use crate::validators::field_errors::FieldError;
+use axum::{
+    http::StatusCode,
+    response::{Html, IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fmt;
+
+// 핸들러 공통 오류
+// API 경로는 {code, message, details} JSON 으로, 브라우저 경로는 HtmlError 로 감싸 HTML 페이지로 응답
+#[derive(Debug)]
+pub enum AppError {
+    // 입력값 검사 실패 (details 에 필드별 오류)
+    Validation(Vec<FieldError>),
+    // 안전하지 않은 목적지 URL (details 에 필드, URL, 사유)
+    UnsafeUrl(UnsafeUrl),
+    Unauthorized(&'static str),
+    // 없거나 삭제된 URL, 잘못되었거나 만료된 인증 코드 등
+    NotFound(&'static str),
+    // 만료되었거나 최대 접속 횟수를 넘은 링크
+    Expired,
+    Conflict(&'static str),
+    // 같은 목적지로 이미 인증된 URL (details 에 기존 단축 URL 정보)
+    ExistingUrl {
+        short_key: String,
+        short_url: String,
+        app_url: Option<String>,
+    },
+    Database(DbError),
+    Internal(String),
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    code: &'static str,
+    message: String,
+    details: Value,
+}
+
+impl AppError {
+    // 필드 하나의 유효성 검사 오류
+    pub fn invalid_field(field: &'static str, message: impl Into<String>) -> Self {
+        AppError::Validation(vec![FieldError {
+            field,
+            message: message.into(),
+        }])
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            AppError::Validation(_) | AppError::UnsafeUrl(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Expired => StatusCode::GONE,
+            AppError::Conflict(_) | AppError::ExistingUrl { .. } => StatusCode::CONFLICT,
+            AppError::Database(_) | AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Validation(_) => "validation_failed",
+            AppError::UnsafeUrl(_) => "unsafe_url",
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::NotFound(_) => "not_found",
+            AppError::Expired => "expired",
+            AppError::Conflict(_) | AppError::ExistingUrl { .. } => "conflict",
+            AppError::Database(_) | AppError::Internal(_) => "internal_error",
+        }
+    }
+
+    // 응답에 담을 메시지 (서버 오류의 원인은 로그에만 남김)
+    pub fn message(&self) -> String {
+        match self {
+            AppError::Validation(_) => "입력값이 올바르지 않습니다.".to_string(),
+            AppError::UnsafeUrl(unsafe_url) => unsafe_url.reason.to_string(),
+            AppError::Unauthorized(message)
+            | AppError::NotFound(message)
+            | AppError::Conflict(message) => message.to_string(),
+            AppError::Expired => "만료된 URL입니다".to_string(),
+            AppError::ExistingUrl { .. } => "같은 목적지로 이미 인증된 URL 이 있습니다".to_string(),
+            AppError::Database(_) | AppError::Internal(_) => {
+                "요청을 처리하지 못했습니다. 잠시 후 다시 시도해주세요.".to_string()
+            }
+        }
+    }
+
+    fn details(&self) -> Value {
+        match self {
+            AppError::Validation(errors) => json!(errors),
+            AppError::UnsafeUrl(unsafe_url) => json!({
+                "field": unsafe_url.field,
+                "url": unsafe_url.url,
+                "reason": unsafe_url.reason.code(),
+            }),
+            AppError::ExistingUrl {
+                short_key,
+                short_url,
+                app_url,
+            } => json!({
+                "shortKey": short_key,
+                "shortUrl": short_url,
+                "appUrl": app_url,
+            }),
+            _ => Value::Null,
+        }
+    }
+
+    fn log(&self) {
+        match self {
+            AppError::Database(_) | AppError::Internal(_) => println!("요청 처리 실패: {}", self),
+            AppError::UnsafeUrl(_) => println!("안전하지 않은 목적지 URL 거부: {}", self),
+            _ => {}
+        }
+    }
+
+    // 브라우저에 보여줄 오류 페이지
+    pub fn into_html_response(self) -> Response {
+        self.log();
+        let title = match self {
+            AppError::NotFound(_) => "링크를 찾을 수 없습니다",
+            AppError::Expired => "만료된 링크입니다",
+            AppError::Database(_) | AppError::Internal(_) => "일시적인 오류가 발생했습니다",
+            _ => "요청을 처리할 수 없습니다",
+        };
+        let html = render(
+            include_str!("templates/error.html"),
+            &[("title", title), ("message", &self.message())],
+        );
+        (self.status(), Html(html)).into_response()
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::UnsafeUrl(unsafe_url) => write!(f, "{}", unsafe_url),
+            AppError::Database(e) => write!(f, "{}", e),
+            AppError::Internal(cause) => write!(f, "{}", cause),
+            _ => write!(f, "{}", self.message()),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<DbError> for AppError {
+    fn from(e: DbError) -> Self {
+        AppError::Database(e)
+    }
+}
+
+impl From<UnsafeUrl> for AppError {
+    fn from(unsafe_url: UnsafeUrl) -> Self {
+        AppError::UnsafeUrl(unsafe_url)
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        self.log();
+        let response = ErrorResponse {
+            code: self.code(),
+            message: self.message(),
+            details: self.details(),
+        };
+        (self.status(), Json(response)).into_response()
+    }
+}
+
+// 브라우저 경로 (리다이렉션, 비밀번호 입력 등) 의 오류는 HTML 페이지로 응답
+pub struct HtmlError(pub AppError);
+
+impl From<AppError> for HtmlError {
+    fn from(e: AppError) -> Self {
+        HtmlError(e)
+    }
+}
+
+impl From<DbError> for HtmlError {
+    fn from(e: DbError) -> Self {
+        HtmlError(AppError::Database(e))
+    }
+}
+
+impl IntoResponse for HtmlError {
+    fn into_response(self) -> Response {
+        self.0.into_html_response()
+    }
+}
@@ -0,0 +1,52 @@
+use crate::error::AppError;
+use axum::{
+    async_trait,
+    extract::{FromRequest, FromRequestParts, Query, Request},
+    http::request::Parts,
+    Json,
+};
+use serde::de::DeserializeOwned;
+
+// axum 의 Json 과 같지만 본문을 읽지 못하면 validation_failed 형식으로 응답 (필드 이름 body)
+pub struct ApiJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ApiJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match Json::<T>::from_request(req, state).await {
+            Ok(Json(value)) => Ok(ApiJson(value)),
+            Err(rejection) => Err(AppError::invalid_field(
+                "body",
+                format!("JSON 본문을 읽을 수 없습니다: {}", rejection.body_text()),
+            )),
+        }
+    }
+}
+
+// axum 의 Query 와 같지만 쿼리 문자열이 잘못되면 validation_failed 형식으로 응답 (필드 이름 query)
+pub struct ApiQuery<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for ApiQuery<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match Query::<T>::from_request_parts(parts, state).await {
+            Ok(Query(value)) => Ok(ApiQuery(value)),
+            Err(rejection) => Err(AppError::invalid_field(
+                "query",
+                format!("쿼리 문자열이 올바르지 않습니다: {}", rejection.body_text()),
+            )),
+        }
+    }
+}
@@ -1,4 +1,8 @@
 pub mod page_handlers;
+pub mod password_handlers;
 pub mod redirect_handlers;
 pub mod short_url_handlers;
+pub mod stats_handlers;
 pub mod verify_handlers;
+pub mod webhook_handlers;
+pub mod well_known_handlers;
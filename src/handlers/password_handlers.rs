@@ -0,0 +1,127 @@
+use crate::error::{AppError, HtmlError};
+use crate::handlers::short_url_handlers::find_by_short_key;
+use crate::schemas::password_schemas::UnlockForm;
+use crate::state::{AppState, FailedAttempts};
+use crate::utils::password::{sign_unlock_token, unlock_cookie_name, verify_password};
+use crate::utils::template::render;
+use axum::{
+    extract::{Path, RawQuery, State},
+    http::header::{LOCATION, SET_COOKIE},
+    http::StatusCode,
+    response::{Html, IntoResponse, Response},
+    Form,
+};
+use chrono::Utc;
+use std::env;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// 잠금 해제 쿠키 유지 시간
+const UNLOCK_TTL_SECONDS: i64 = 24 * 3600;
+// 링크별 실패 허용 횟수와 집계 시간 창 (인스턴스마다 따로 집계)
+const MAX_FAILED_ATTEMPTS: u32 = 5;
+const FAILED_ATTEMPTS_WINDOW: Duration = Duration::from_secs(15 * 60);
+
+// 원래 요청의 쿼리 문자열을 유지한 단축 URL 경로
+fn link_path(short_key: &str, query: Option<&str>) -> String {
+    match query.filter(|query| !query.is_empty()) {
+        Some(query) => format!("/{}?{}", short_key, query),
+        None => format!("/{}", short_key),
+    }
+}
+
+// 비밀번호 입력 페이지
+pub fn password_form(
+    short_key: &str,
+    query: Option<&str>,
+    error: &str,
+    status: StatusCode,
+) -> Response {
+    let html = render(
+        include_str!("../templates/password.html"),
+        &[("action", &link_path(short_key, query)), ("error", error)],
+    );
+    (status, Html(html)).into_response()
+}
+
+// 비밀번호 확인 전에 시도 횟수를 먼저 차감
+// 확인(Argon2)에 시간이 걸리므로, 결과를 보고 나서 기록하면 동시에 보낸 요청이 모두 제한을 통과함
+// 허용 횟수를 넘었으면 false
+fn reserve_attempt(state: &AppState, url_id: i64) -> bool {
+    let mut attempts = state
+        .password_attempts
+        .entry(url_id)
+        .or_insert(FailedAttempts {
+            count: 0,
+            window_start: Instant::now(),
+        });
+    if attempts.window_start.elapsed() >= FAILED_ATTEMPTS_WINDOW {
+        *attempts = FailedAttempts {
+            count: 0,
+            window_start: Instant::now(),
+        };
+    }
+    if attempts.count >= MAX_FAILED_ATTEMPTS {
+        return false;
+    }
+    attempts.count += 1;
+    true
+}
+
+// 비밀번호 확인 핸들러
+// 맞으면 서명된 쿠키를 발급하고 단축 URL 로 다시 이동
+pub async fn unlock_link_handler(
+    Path(short_key): Path<String>,
+    State(state): State<Arc<AppState>>,
+    RawQuery(query): RawQuery,
+    Form(form): Form<UnlockForm>,
+) -> Result<Response, HtmlError> {
+    let url = find_by_short_key(&state, &short_key)
+        .await?
+        .filter(|url| url.is_verified)
+        .ok_or(AppError::NotFound("URL을 찾을 수 없습니다"))?;
+    let location = link_path(&short_key, query.as_deref());
+    let Some(password_hash) = url.password_hash else {
+        return Ok((StatusCode::SEE_OTHER, [(LOCATION, location)]).into_response());
+    };
+
+    if !reserve_attempt(&state, url.id) {
+        return Ok(password_form(
+            &short_key,
+            query.as_deref(),
+            "시도 횟수를 초과했습니다. 잠시 후 다시 시도해주세요.",
+            StatusCode::TOO_MANY_REQUESTS,
+        ));
+    }
+    let hash = password_hash.clone();
+    let valid = tokio::task::spawn_blocking(move || verify_password(&form.password, &hash))
+        .await
+        .unwrap_or(false);
+    if !valid {
+        return Ok(password_form(
+            &short_key,
+            query.as_deref(),
+            "비밀번호가 올바르지 않습니다.",
+            StatusCode::UNAUTHORIZED,
+        ));
+    }
+    // 맞으면 차감한 시도 횟수 초기화
+    state.password_attempts.remove(&url.id);
+
+    let expires = Utc::now().timestamp() + UNLOCK_TTL_SECONDS;
+    let token = sign_unlock_token(&state.cookie_secret, url.id, &password_hash, expires);
+    let secure = env::var("COOKIE_SECURE").unwrap_or("false".to_string()) == "true";
+    let cookie = format!(
+        "{}={}; Path=/{}; Max-Age={}; HttpOnly; SameSite=Lax{}",
+        unlock_cookie_name(url.id),
+        token,
+        short_key,
+        UNLOCK_TTL_SECONDS,
+        if secure { "; Secure" } else { "" }
+    );
+    Ok((
+        StatusCode::SEE_OTHER,
+        [(LOCATION, location), (SET_COOKIE, cookie)],
+    )
+        .into_response())
+}
@@ -1,115 +1,320 @@
+use crate::error::{AppError, HtmlError};
+use crate::handlers::password_handlers::password_form;
+use crate::handlers::short_url_handlers::find_by_short_key;
+use crate::models::click_event::ClickEvent;
 use crate::state::CacheEntry;
-use crate::utils::converter::split_short_key;
+use crate::utils::android_intent::android_intent_url;
+use crate::utils::open_graph::OpenGraph;
+use crate::utils::password::has_unlock_cookie;
+use crate::utils::platform::{is_preview_crawler, Platform};
+use crate::utils::sanitizer::sanitize_head_html;
+use crate::utils::short_url::short_url;
+use crate::utils::template::render;
+use crate::validators::validate_url::is_safe_link;
 use crate::AppState;
 use axum::{
-    body::Body, extract::Path, http::Request, http::StatusCode, response::Html,
+    extract::{ConnectInfo, Path, RawQuery, State},
+    http::header::LOCATION,
+    http::HeaderMap,
+    http::StatusCode,
+    response::Html,
     response::IntoResponse,
+    response::Response,
 };
+use chrono::{DateTime, Utc};
 use serde_json::json;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::Mutex;
+
+// 기본 캐시 유지 시간
+const CACHE_TTL: Duration = Duration::from_secs(3600);
+
+// 만료되었거나 최대 접속 횟수를 넘은 URL 의 응답
+fn expired_response(expired_action: &str, expired_fallback_url: &str) -> Response {
+    match expired_action {
+        "redirect" if !expired_fallback_url.is_empty() => {
+            (StatusCode::FOUND, [(LOCATION, expired_fallback_url)]).into_response()
+        }
+        "page" => (
+            StatusCode::GONE,
+            Html(include_str!("../templates/expired.html")),
+        )
+            .into_response(),
+        _ => HtmlError(AppError::Expired).into_response(),
+    }
+}
+
+// 저장된 리다이렉트 상태 코드 (없거나 3xx 가 아니면 302)
+fn redirect_status_code(status: Option<u64>) -> StatusCode {
+    status
+        .and_then(|status| u16::try_from(status).ok())
+        .and_then(|status| StatusCode::from_u16(status).ok())
+        .filter(StatusCode::is_redirection)
+        .unwrap_or(StatusCode::FOUND)
+}
 
 // 리다이렉션 핸들러
 pub async fn redirect_to_original_handler(
     Path(short_key): Path<String>,
-    state: axum::extract::State<Arc<Mutex<AppState>>>,
-    req: Request<Body>,
-) -> impl IntoResponse {
-    let state = state.lock().await;
-
-    // 캐시 확인
-    let cache = state.cache.read().await;
-    if let Some(entry) = cache.get(&short_key) {
-        println!("{:?}", entry.expiry);
-        if entry.expiry > Instant::now() {
-            // entry.data 를 분리
-            let data: serde_json::Value = serde_json::from_str(&entry.data).unwrap();
-
-            let ios_deep_link = data["ios_deep_link"].as_str().unwrap_or("");
-            let ios_fallback_url = data["ios_fallback_url"].as_str().unwrap_or("");
-            let android_deep_link = data["android_deep_link"].as_str().unwrap_or("");
-            let android_fallback_url = data["android_fallback_url"].as_str().unwrap_or("");
-            let default_fallback_url = data["default_fallback_url"].as_str().unwrap_or("");
-            let head_html = data["head_html"].as_str().unwrap_or("");
-
-            let webhook_url = data["webhook_url"].as_str().unwrap_or("");
-            if webhook_url != "" {
-                // 웹훅 보내기
-                let user_agent = req.headers().get("User-Agent").unwrap().to_str().unwrap();
-                let client = reqwest::Client::new();
-                if let Err(e) = client
-                    .post(webhook_url)
-                    .json(&json!({
-                        "short_key": short_key,
-                        "user_agent": user_agent,
-                    }))
-                    .send()
-                    .await
-                {
-                    println!("웹훅 전송 실패: {}", e);
-                }
-            }
-            let success_html = include_str!("../templates/redirect.html")
-                .replace("{ios_deep_link}", &ios_deep_link)
-                .replace("{ios_fallback_url}", &ios_fallback_url)
-                .replace("{android_deep_link}", &android_deep_link)
-                .replace("{android_fallback_url}", &android_fallback_url)
-                .replace("{default_fallback_url}", &default_fallback_url)
-                .replace("{head_html}", &head_html);
-            return (StatusCode::OK, Html(success_html)).into_response();
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    RawQuery(query): RawQuery,
+    headers: HeaderMap,
+) -> Result<Response, HtmlError> {
+    // 캐시 확인 (읽은 값은 복사해 두고 샤드 잠금은 즉시 해제)
+    let cached = state
+        .cache
+        .get(&short_key)
+        .filter(|entry| entry.expiry > Instant::now())
+        .map(|entry| entry.data.clone());
+
+    let data = match cached {
+        Some(data) => serde_json::from_str::<serde_json::Value>(&data).unwrap(),
+        None => {
+            // 캐시에 없으면 DB에서 조회 (인증된 URL 만)
+            let url = find_by_short_key(&state, &short_key)
+                .await?
+                .filter(|url| url.is_verified)
+                .ok_or(AppError::NotFound("URL을 찾을 수 없습니다"))?;
+            let data = json!({
+                "id": url.id,
+                "ios_deep_link": url.ios_deep_link,
+                "ios_fallback_url": url.ios_fallback_url,
+                "android_deep_link": url.android_deep_link,
+                "android_fallback_url": url.android_fallback_url,
+                "android_package_name": url.android_package_name,
+                "default_fallback_url": url.default_fallback_url,
+                "webhook_url": url.webhook_url,
+                // 정제 이전에 저장된 값도 있으므로 캐시에 넣기 전에 한 번 더 정제
+                "head_html": sanitize_head_html(&url.head_html),
+                "og_title": url.og_title,
+                "og_description": url.og_description,
+                "og_image": url.og_image,
+                "twitter_card": url.twitter_card,
+                "redirect_status": url.redirect_status,
+                "activates_at": url.activates_at,
+                "expires_at": url.expires_at,
+                "max_clicks": url.max_clicks,
+                "expired_action": url.expired_action,
+                "expired_fallback_url": url.expired_fallback_url,
+                "password_hash": url.password_hash
+            });
+            // URL 의 만료 시각이 기본 캐시 유지 시간보다 빠르면 그때까지만 캐시
+            let ttl = url
+                .expires_at
+                .and_then(|expires_at| (expires_at - Utc::now()).to_std().ok())
+                .map_or(CACHE_TTL, |remaining| remaining.min(CACHE_TTL));
+            state.cache.insert(
+                short_key.clone(),
+                CacheEntry {
+                    data: serde_json::to_string(&data).unwrap(),
+                    expiry: Instant::now() + ttl,
+                },
+            );
+            data
         }
+    };
+
+    // 접속 가능 기간과 최대 접속 횟수 확인
+    let now = Utc::now();
+    let timestamp = |key: &str| {
+        data[key]
+            .as_str()
+            .and_then(|value| value.parse::<DateTime<Utc>>().ok())
+    };
+    if timestamp("activates_at").is_some_and(|activates_at| now < activates_at) {
+        return Err(AppError::NotFound("아직 활성화되지 않은 URL입니다").into());
+    }
+    let expired_action = data["expired_action"].as_str().unwrap_or("gone");
+    let expired_fallback_url = data["expired_fallback_url"].as_str().unwrap_or("");
+    if timestamp("expires_at").is_some_and(|expires_at| now >= expires_at) {
+        return Ok(expired_response(expired_action, expired_fallback_url));
     }
-    drop(cache);
+    // 비밀번호 보호 링크는 잠금 해제 쿠키가 없으면 입력 페이지부터 보여줌
+    let url_id = data["id"].as_i64().unwrap_or_default();
+    if let Some(password_hash) = data["password_hash"].as_str() {
+        if !has_unlock_cookie(
+            &headers,
+            &state.cookie_secret,
+            url_id,
+            password_hash,
+            now.timestamp(),
+        ) {
+            return Ok(password_form(
+                &short_key,
+                query.as_deref(),
+                "",
+                StatusCode::OK,
+            ));
+        }
+    }
+    // 미리보기 크롤러에는 리다이렉트 없이 메타데이터만 담은 페이지를 보여줌 (접속 수에도 포함하지 않음)
+    let user_agent = headers
+        .get("User-Agent")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+    if is_preview_crawler(user_agent) {
+        let text = |key: &str| data[key].as_str().unwrap_or("");
+        let open_graph = OpenGraph {
+            title: text("og_title"),
+            description: text("og_description"),
+            image: text("og_image"),
+            twitter_card: text("twitter_card"),
+        };
+        let short_url = short_url(&short_key);
+        let preview_html = render(
+            include_str!("../templates/preview.html"),
+            &[
+                (
+                    "head_html",
+                    &open_graph.head_html(&short_url, text("head_html")),
+                ),
+                ("short_url", &short_url),
+            ],
+        );
+        return Ok((StatusCode::OK, Html(preview_html)).into_response());
+    }
+    if !data["max_clicks"].is_null() && !state.urls.consume_click(url_id).await? {
+        return Ok(expired_response(expired_action, expired_fallback_url));
+    }
+
+    // javascript:, data: 등 스크립트를 실행할 수 있는 링크는 이동 대상에서 제외
+    let link = |key: &str| {
+        let link = data[key].as_str().unwrap_or("");
+        if is_safe_link(link) {
+            link
+        } else {
+            println!("안전하지 않은 링크 제외 ({}): {}", short_key, key);
+            ""
+        }
+    };
+    let ios_deep_link = link("ios_deep_link");
+    let ios_fallback_url = link("ios_fallback_url");
+    let android_deep_link = link("android_deep_link");
+    let android_fallback_url = link("android_fallback_url");
+    let android_package_name = data["android_package_name"].as_str().unwrap_or("");
+    let default_fallback_url = link("default_fallback_url");
+    let head_html = data["head_html"].as_str().unwrap_or("");
 
-    let (url_id, request_random_key) = split_short_key(&short_key);
+    let mut event = ClickEvent::from_request(
+        url_id,
+        &short_key,
+        &headers,
+        query,
+        peer.ip(),
+        &state.trusted_proxies,
+        &state.visitor_secret,
+    );
 
-    // 캐시에 없으면 DB에서 조회
-    match state.db.query_row(
-        "SELECT random_key, ios_deep_link, ios_fallback_url, android_deep_link, android_fallback_url, default_fallback_url, webhook_url, head_html FROM urls WHERE id = ?1 and is_deleted = 0 and is_verified = 1",
-        [&url_id],
-            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?, row.get::<_, String>(3)?, row.get::<_, String>(4)?, row.get::<_, String>(5)?, row.get::<_, String>(6)?, row.get::<_, String>(7)?)),
-        ) {
-        Ok((random_key, ios_deep_link, ios_fallback_url, android_deep_link, android_fallback_url, default_fallback_url, webhook_url, head_html)) => {
-            // random_key 가 일치하는지 확인
-            if random_key != request_random_key {
-                return (StatusCode::NOT_FOUND, "URL을 찾을 수 없습니다").into_response();
+    // 이동할 위치 결정 (딥링크 우선, 없으면 플랫폼 폴백, 그것도 없으면 기본 URL)
+    let (deep_link, fallback_url) = match event.platform {
+        Platform::Ios => (ios_deep_link, ios_fallback_url),
+        Platform::Android => (android_deep_link, android_fallback_url),
+        Platform::Other => ("", default_fallback_url),
+    };
+    let location = if !deep_link.is_empty() {
+        deep_link
+    } else if !fallback_url.is_empty() {
+        fallback_url
+    } else {
+        default_fallback_url
+    };
+    event.destination = location.to_string();
+
+    // Android 는 타이머 대신 intent:// URL 로 앱을 열고, 앱이 없을 때의 이동은 Chrome 에 맡김
+    // 폴백 URL 이 없으면 패키지 이름이 있을 때는 Play 스토어, 없을 때는 기본 URL 로 이동
+    let mut android_intent = String::new();
+    if event.platform == Platform::Android && !deep_link.is_empty() {
+        let intent_fallback_url = if !android_fallback_url.is_empty() {
+            android_fallback_url
+        } else if !android_package_name.is_empty() {
+            ""
+        } else {
+            default_fallback_url
+        };
+        if let Some(intent) =
+            android_intent_url(deep_link, android_package_name, intent_fallback_url)
+        {
+            event.destination = intent.clone();
+            android_intent = intent;
+        }
+    }
+
+    // 접속 기록은 응답을 늦추지 않도록 별도 작업으로 저장
+    {
+        let state = state.clone();
+        let event = event.clone();
+        tokio::spawn(async move {
+            if let Err(e) = state.clicks.record(event).await {
+                println!("접속 기록 저장 실패: {}", e);
             }
-            let data = json!({
-                "ios_deep_link": ios_deep_link,
-                "ios_fallback_url": ios_fallback_url,
-                "android_deep_link": android_deep_link,
-                "android_fallback_url": android_fallback_url,
-                "default_fallback_url": default_fallback_url,
-                "webhook_url": webhook_url,
-                "head_html": head_html
-            });
-            let data_string = serde_json::to_string(&data).unwrap();
-            state.cache.write().await.insert(short_key.clone(), CacheEntry {
-                data: data_string,
-                expiry: Instant::now() + Duration::from_secs(3600),
-            });
+        });
+    }
 
-            if webhook_url != "" {
-                // 웹훅 보내기
-                let user_agent = req.headers().get("User-Agent").unwrap().to_str().unwrap();
-                let client = reqwest::Client::new();
-                if let Err(e) = client.post(webhook_url).json(&json!({
-                    "short_key": short_key,
-                    "user_agent": user_agent,
-                })).send().await {
-                    println!("웹훅 전송 실패: {}", e);
-                }
+    let webhook_url = data["webhook_url"].as_str().unwrap_or("").to_string();
+    if !webhook_url.is_empty() {
+        // 웹훅은 대기열에 넣기만 하고 전송은 워커가 처리
+        let payload = serde_json::to_string(&event).unwrap();
+        let state = state.clone();
+        tokio::spawn(async move {
+            match state
+                .webhooks
+                .enqueue(event.url_id, &webhook_url, &payload)
+                .await
+            {
+                Ok(_) => state.webhook_notify.notify_one(),
+                Err(e) => println!("웹훅 대기열 추가 실패: {}", e),
             }
-            let success_html = include_str!("../templates/redirect.html")
-                .replace("{ios_deep_link}", &ios_deep_link)
-                .replace("{ios_fallback_url}", &ios_fallback_url)
-                .replace("{android_deep_link}", &android_deep_link)
-                .replace("{android_fallback_url}", &android_fallback_url)
-                .replace("{default_fallback_url}", &default_fallback_url)
-                .replace("{head_html}", &head_html);
-            return (StatusCode::OK, Html(success_html)).into_response()
-        },
-        Err(_) => (StatusCode::NOT_FOUND, "URL을 찾을 수 없습니다").into_response(),
+        });
+    }
+
+    // 딥링크를 시도할 필요가 없으면 바로 HTTP 리다이렉트
+    if deep_link.is_empty() {
+        let status = redirect_status_code(data["redirect_status"].as_u64());
+        return Ok((status, [(LOCATION, location)]).into_response());
+    }
+
+    // 딥링크 시도 후 폴백이 필요한 경우에만 HTML 페이지 반환
+    let success_html = render(
+        include_str!("../templates/redirect.html"),
+        &[
+            ("ios_deep_link", ios_deep_link),
+            ("ios_fallback_url", ios_fallback_url),
+            ("android_deep_link", android_deep_link),
+            ("android_fallback_url", android_fallback_url),
+            ("android_intent_url", &android_intent),
+            ("default_fallback_url", default_fallback_url),
+            ("head_html", head_html),
+        ],
+    );
+    Ok((StatusCode::OK, Html(success_html)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redirect_status_falls_back_to_302() {
+        for status in [301, 302, 307, 308] {
+            assert_eq!(redirect_status_code(Some(status)).as_u16(), status as u16);
+        }
+        // 0x1_0000 + 301 이 u16 으로 잘려 301 이 되지 않도록 범위를 넘으면 기본값
+        for status in [
+            None,
+            Some(200),
+            Some(404),
+            Some(500),
+            Some(99),
+            Some(65_837),
+        ] {
+            assert_eq!(
+                redirect_status_code(status),
+                StatusCode::FOUND,
+                "{:?}",
+                status
+            );
+        }
     }
 }
@@ -1,27 +1,44 @@
+use crate::error::AppError;
+use crate::extractors::{ApiJson, ApiQuery};
+use crate::models::repository::{ApiKey, DbError, NewUrl, Url, UrlUpdate};
+use crate::schemas::short_url_schemas::{
+    CreateUrlRequest, CreateUrlResponse, ListUrlsQuery, ListUrlsResponse, UpdateUrlRequest,
+    UrlResponse,
+};
+use crate::state::AppState;
+use crate::utils::converter::{make_short_key, split_short_key};
+use crate::utils::fetcher::extract_head_html;
+use crate::utils::generator::{generate_random_string, generate_webhook_secret};
+use crate::utils::password::hash_password;
+use crate::utils::sanitizer::sanitize_head_html;
+use crate::utils::short_url::{app_url, base_url, short_url};
+use crate::validators::field_errors::{FieldError, FieldErrors};
+use crate::validators::validate_url::{
+    validate_alias, validate_android_package_name, validate_deep_link, validate_email,
+    validate_expires_at, validate_fallback_url, validate_head_html, validate_link_limits,
+    validate_open_graph, validate_password, validate_permanent_redirect, validate_redirect_status,
+    validate_url, validate_webhook_url,
+};
 use axum::{
+    extract::{Path, State},
     http::StatusCode,
-    response::IntoResponse,
-    Json,
+    response::{IntoResponse, Response},
+    Extension, Json,
 };
+use chrono::{DateTime, Utc};
 use lettre::{transport::smtp::authentication::Credentials, Message, SmtpTransport, Transport};
 use sha2::{Digest, Sha256};
-use std::sync::Arc;
-use tokio::sync::Mutex;
-use crate::utils::generator::generate_random_string;
-use crate::utils::converter::id_to_key;
-use crate::validators::validate_url::{validate_email, validate_fallback_url, validate_url, validate_webhook_url};
-use crate::schemas::short_url_schemas::{CreateUrlRequest, CreateUrlResponse};
-use scraper::Html as ScraperHtml;
 use std::env;
-use crate::state::AppState;
+use std::sync::Arc;
 
+// 단축 키가 충돌할 때 다시 발급하는 최대 횟수
+const MAX_KEY_ATTEMPTS: usize = 5;
 
 async fn send_email(email: String, code: String) -> Result<(), lettre::transport::smtp::Error> {
-    let host = env::var("SERVER_HOST").unwrap_or("127.0.0.1".to_string());
-    let port = env::var("SERVER_PORT").unwrap_or("3000".to_string());
     let email_body = format!(
-        "http://{}:{}/v1/verify/{}\n\n이 코드는 5분 동안 유효합니다.",
-        host, port, code
+        "{}/v1/verify/{}\n\n이 코드는 5분 동안 유효합니다.",
+        base_url(),
+        code
     );
 
     let from_email = env::var("EMAIL_ADDRESS").unwrap_or("lee@lou2.kr".to_string());
@@ -45,134 +62,976 @@ async fn send_email(email: String, code: String) -> Result<(), lettre::transport
         .body(email_body.as_bytes().to_vec())
         .unwrap();
 
-    match mailer.send(&email) {
+    // SMTP 전송은 블로킹이므로 런타임 워커 밖에서 실행
+    match tokio::task::spawn_blocking(move || mailer.send(&email))
+        .await
+        .unwrap()
+    {
         Ok(_) => Ok(()),
         Err(e) => Err(e),
     }
 }
 
-fn extract_head_html(html: &str) -> String {
-    let document = ScraperHtml::parse_document(html);
-    let selector = scraper::Selector::parse("head").unwrap();
-    if let Some(head) = document.select(&selector).next() {
-        head.html()
+// 목적지 URL 조합의 해시 (중복 생성 방지용)
+// options 는 목적지가 같아도 동작이 다른 URL 이 합쳐지지 않도록 덧붙이는 값
+fn hash_destinations(
+    ios_deep_link: &str,
+    ios_fallback_url: &str,
+    android_deep_link: &str,
+    android_fallback_url: &str,
+    default_fallback_url: &str,
+    options: &str,
+) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(format!(
+        "{}{}{}{}{}{}",
+        ios_deep_link,
+        ios_fallback_url,
+        android_deep_link,
+        android_fallback_url,
+        default_fallback_url,
+        options
+    ));
+    format!("{:x}", hasher.finalize())
+}
+
+// 기본값(302) 이면 빈 문자열 (기존 URL 의 해시가 바뀌지 않도록)
+fn redirect_status_key(redirect_status: i32) -> String {
+    if redirect_status == 302 {
+        String::new()
     } else {
+        format!("|redirect_status|{}", redirect_status)
+    }
+}
+
+// 제한이 없으면 빈 문자열 (기존 URL 의 해시가 바뀌지 않도록)
+fn limits_key(
+    activates_at: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+    max_clicks: Option<i32>,
+    expired_action: &str,
+    expired_fallback_url: &str,
+) -> String {
+    if activates_at.is_none() && expires_at.is_none() && max_clicks.is_none() {
+        return String::new();
+    }
+    format!(
+        "|{:?}|{:?}|{:?}|{}|{}",
+        activates_at, expires_at, max_clicks, expired_action, expired_fallback_url
+    )
+}
+
+// 비밀번호 해시 (Argon2 는 CPU 를 오래 쓰므로 별도 스레드에서 실행)
+async fn hash_link_password(password: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || hash_password(&password))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+// 패키지 이름이 없으면 빈 문자열 (기존 URL 의 해시가 바뀌지 않도록)
+fn package_key(android_package_name: &str) -> String {
+    if android_package_name.is_empty() {
         String::new()
+    } else {
+        format!("|android_package|{}", android_package_name)
+    }
+}
+
+// 미리보기 메타데이터가 모두 비어 있으면 빈 문자열 (기존 URL 의 해시가 바뀌지 않도록)
+fn open_graph_key(
+    og_title: &str,
+    og_description: &str,
+    og_image: &str,
+    twitter_card: &str,
+) -> String {
+    if [og_title, og_description, og_image, twitter_card]
+        .iter()
+        .all(|value| value.is_empty())
+    {
+        return String::new();
+    }
+    format!(
+        "|open_graph|{:?}|{:?}|{:?}|{:?}",
+        og_title, og_description, og_image, twitter_card
+    )
+}
+
+// 비밀번호 보호 링크와 별칭 링크는 같은 목적지의 다른 요청과 합쳐지지 않도록 해시에 고유한 값을 덧붙임
+fn identity_key(password_protected: bool, random_key: &str, alias: Option<&str>) -> String {
+    let mut key = String::new();
+    if password_protected {
+        key.push_str(&format!("|password|{}", random_key));
+    }
+    if let Some(alias) = alias {
+        key.push_str(&format!("|alias|{}", alias));
+    }
+    key
+}
+
+// 이 URL 로 해석되는 모든 키로 캐시된 항목 제거
+fn invalidate_cache(state: &AppState, short_keys: &[String]) {
+    for short_key in short_keys {
+        state.cache.remove(short_key);
+    }
+}
+
+// 단축 키가 다른 URL 에 이미 쓰였는지 (별칭과 발급한 키는 삭제된 URL 의 것도 포함)
+async fn short_key_taken(
+    state: &AppState,
+    short_key: &str,
+    own_id: Option<i64>,
+) -> Result<bool, DbError> {
+    if state.urls.alias_exists(short_key).await?
+        || state.urls.generated_key_exists(short_key).await?
+    {
+        return Ok(true);
+    }
+    Ok(find_by_short_key(state, short_key)
+        .await?
+        .is_some_and(|url| Some(url.id) != own_id))
+}
+
+// 배포 설정의 인코딩 방식으로 단축 키 발급
+// 무작위 방식만 충돌 시 다시 시도하고 (결정적인 방식은 다시 호출해도 같은 키),
+// 끝내 실패하면 기존 방식의 키도 사용 중인지 확인한 뒤 저장
+// 기존 방식의 키까지 사용 중이면 None
+async fn assign_short_key(
+    state: &AppState,
+    id: i64,
+    random_key: &str,
+) -> Result<Option<String>, DbError> {
+    let attempts = if state.key_codec.is_deterministic() {
+        1
+    } else {
+        MAX_KEY_ATTEMPTS
+    };
+    for _ in 0..attempts {
+        let short_key = state.key_codec.encode(id, random_key);
+        if claim_short_key(state, id, &short_key).await? {
+            return Ok(Some(short_key));
+        }
+    }
+    let fallback = make_short_key(id, random_key);
+    println!(
+        "단축 키 발급 실패 ({}회 충돌), 기존 방식의 키를 사용합니다: id={} key={}",
+        attempts, id, fallback
+    );
+    if claim_short_key(state, id, &fallback).await? {
+        return Ok(Some(fallback));
+    }
+    println!("기존 방식의 키도 사용 중입니다: id={} key={}", id, fallback);
+    Ok(None)
+}
+
+// 사용 중이 아니면 발급한 키로 저장
+// 검사 이후 다른 요청이 같은 키를 먼저 저장했으면 (유니크 인덱스 위반) false
+async fn claim_short_key(state: &AppState, id: i64, short_key: &str) -> Result<bool, DbError> {
+    if short_key_taken(state, short_key, Some(id)).await? {
+        return Ok(false);
+    }
+    match state.urls.set_generated_key(id, short_key).await {
+        Ok(()) => Ok(true),
+        Err(e) if e.is_unique_violation() => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+// 웹훅 목적지 정책 검사 (정책 위반은 webhookUrl 필드 오류로 응답)
+async fn check_webhook_policy(state: &AppState, webhook_url: &str) -> Result<(), AppError> {
+    if webhook_url.is_empty() {
+        return Ok(());
+    }
+    state
+        .webhook_policy
+        .check(webhook_url)
+        .await
+        .map_err(|e| AppError::invalid_field("webhookUrl", e.to_string()))?;
+    Ok(())
+}
+
+// 만료 시 동작 기본값
+fn default_expired_action(expired_fallback_url: &str) -> String {
+    if expired_fallback_url.is_empty() {
+        "gone".to_string()
+    } else {
+        "redirect".to_string()
     }
 }
 
+// 안전성 검사 대상 중 웹 주소만 남김 (앱 스킴 딥링크는 차단 목록과 평판 서비스로 판단할 수 없음)
+fn web_destinations<'a>(destinations: &[(&'static str, &'a str)]) -> Vec<(&'static str, &'a str)> {
+    destinations
+        .iter()
+        .filter(|(_, url)| {
+            reqwest::Url::parse(url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+        })
+        .copied()
+        .collect()
+}
+
 // URL 단축 핸들러
+// API 키로 인증된 요청은 키 소유자의 URL 로 바로 활성화하고, 익명 요청은 이메일 인증을 거침
 pub async fn create_short_url_handler(
-    state: axum::extract::State<Arc<Mutex<AppState>>>,
-    Json(payload): Json<CreateUrlRequest>,
-) -> impl IntoResponse {
-    let state_clone = state.clone();
-    let state = state.lock().await;
-    
-    // 유효성 검사
-    fn validate_data(payload: &CreateUrlRequest) -> Result<(), String> {
-        validate_email(&payload.email)?;
-        validate_url(&payload.default_fallback_url)?;
-        validate_webhook_url(&payload.webhook_url)?;
-        validate_fallback_url(&payload.default_fallback_url)?;
-        Ok(())
+    State(state): State<Arc<AppState>>,
+    api_key: Option<Extension<ApiKey>>,
+    ApiJson(payload): ApiJson<CreateUrlRequest>,
+) -> Result<Response, AppError> {
+    let api_key = api_key.map(|Extension(api_key)| api_key);
+    let email = match &api_key {
+        Some(api_key) => api_key.owner_email.clone(),
+        None => payload.email.clone(),
+    };
+
+    // 유효성 검사 (모든 필드를 검사해 오류를 한 번에 반환)
+    fn validate_data(
+        email: &str,
+        redirect_status: i32,
+        expired_action: &str,
+        expired_fallback_url: &str,
+        payload: &CreateUrlRequest,
+    ) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+        errors.check("email", validate_email(email));
+        errors.check("iosDeepLink", validate_deep_link(&payload.ios_deep_link));
+        errors.check(
+            "iosFallbackUrl",
+            validate_fallback_url(&payload.ios_fallback_url),
+        );
+        errors.check(
+            "androidDeepLink",
+            validate_deep_link(&payload.android_deep_link),
+        );
+        errors.check(
+            "androidFallbackUrl",
+            validate_fallback_url(&payload.android_fallback_url),
+        );
+        errors.check(
+            "androidPackageName",
+            validate_android_package_name(&payload.android_package_name),
+        );
+        errors.check(
+            "defaultFallbackUrl",
+            validate_url(&payload.default_fallback_url),
+        );
+        errors.check("webhookUrl", validate_webhook_url(&payload.webhook_url));
+        errors.check("headHtml", validate_head_html(&payload.head_html));
+        errors.check("redirectStatus", validate_redirect_status(redirect_status));
+        let open_graph = &payload.open_graph;
+        validate_open_graph(
+            &mut errors,
+            open_graph.title.as_deref().unwrap_or(""),
+            open_graph.description.as_deref().unwrap_or(""),
+            open_graph.image.as_deref().unwrap_or(""),
+            open_graph.twitter_card.as_deref().unwrap_or(""),
+        );
+        validate_link_limits(
+            &mut errors,
+            payload.activates_at,
+            payload.expires_at,
+            payload.max_clicks,
+            expired_action,
+            expired_fallback_url,
+        );
+        errors.check(
+            "redirectStatus",
+            validate_permanent_redirect(
+                redirect_status,
+                payload.activates_at,
+                payload.expires_at,
+                payload.max_clicks,
+                payload.password.as_deref().is_some_and(|p| !p.is_empty()),
+            ),
+        );
+        errors.check(
+            "expiresAt",
+            validate_expires_at(payload.expires_at, Utc::now()),
+        );
+        errors.check(
+            "password",
+            validate_password(payload.password.as_deref().unwrap_or("")),
+        );
+        if let Some(alias) = &payload.alias {
+            errors.check("alias", validate_alias(alias));
+        }
+        errors.into_result()
     }
 
-    if let Err(e) = validate_data(&payload) {
-        return (StatusCode::BAD_REQUEST, e).into_response();
+    let redirect_status = payload.redirect_status.unwrap_or(302);
+    let expired_fallback_url = payload.expired_fallback_url.clone().unwrap_or_default();
+    let expired_action = payload
+        .expired_action
+        .clone()
+        .unwrap_or_else(|| default_expired_action(&expired_fallback_url));
+    if let Err(e) = validate_data(
+        &email,
+        redirect_status,
+        &expired_action,
+        &expired_fallback_url,
+        &payload,
+    ) {
+        return Err(AppError::Validation(e));
     }
+    check_webhook_policy(&state, &payload.webhook_url).await?;
+    state
+        .url_safety
+        .check(&web_destinations(&[
+            ("defaultFallbackUrl", &payload.default_fallback_url),
+            ("iosFallbackUrl", &payload.ios_fallback_url),
+            ("androidFallbackUrl", &payload.android_fallback_url),
+            ("expiredFallbackUrl", &expired_fallback_url),
+            ("iosDeepLink", &payload.ios_deep_link),
+            ("androidDeepLink", &payload.android_deep_link),
+        ]))
+        .await?;
+
+    // 별칭은 삭제된 URL 의 것이나 기존에 생성된 키와도 겹치면 안 됨
+    if let Some(alias) = &payload.alias {
+        if short_key_taken(&state, alias, None).await? {
+            return Err(AppError::Conflict("이미 사용 중인 별칭입니다"));
+        }
+    }
+
+    let password_hash = match payload.password.clone().filter(|p| !p.is_empty()) {
+        Some(password) => Some(
+            hash_link_password(password)
+                .await
+                .map_err(AppError::Internal)?,
+        ),
+        None => None,
+    };
 
     // 고유 ID 생성
     let random_key = generate_random_string(4);
-    let mut hasher = Sha256::new();
-    hasher.update(format!(
-        "{}{}{}{}{}",
-        payload.ios_deep_link,
-        payload.ios_fallback_url,
-        payload.android_deep_link,
-        payload.android_fallback_url,
-        payload.default_fallback_url
-    ));
-    let hashed_value = format!("{:x}", hasher.finalize());
+    let hashed_value = hash_destinations(
+        &payload.ios_deep_link,
+        &payload.ios_fallback_url,
+        &payload.android_deep_link,
+        &payload.android_fallback_url,
+        &payload.default_fallback_url,
+        &(limits_key(
+            payload.activates_at,
+            payload.expires_at,
+            payload.max_clicks,
+            &expired_action,
+            &expired_fallback_url,
+        ) + &redirect_status_key(redirect_status)
+            + &package_key(&payload.android_package_name)
+            + &open_graph_key(
+                payload.open_graph.title.as_deref().unwrap_or(""),
+                payload.open_graph.description.as_deref().unwrap_or(""),
+                payload.open_graph.image.as_deref().unwrap_or(""),
+                payload.open_graph.twitter_card.as_deref().unwrap_or(""),
+            )
+            + &identity_key(
+                password_hash.is_some(),
+                &random_key,
+                payload.alias.as_deref(),
+            )),
+    );
 
-    // hashed_value 로 이미 있으면 그걸 그대로 반환
-    match state.db.query_row(
-        "SELECT id, email, random_key, is_verified FROM urls WHERE hashed_value = ?1 and is_deleted = 0",
-        [&hashed_value],
-        |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?, row.get::<_, i64>(3)?)),
-    ) {
-        Ok((id, email_address, random_key, is_verified)) => {
-            if is_verified == 1 {
-                return (StatusCode::CONFLICT, "이미 인증된 이메일입니다.").into_response();
-            }
-            let unique_key = id_to_key(id);
-            let short_key = (&random_key[..2]).to_string() + &unique_key + &random_key[2..];
-            // 이메일 인증 테이블에 추가
-            let code = generate_random_string(8);
-            let expires_at = chrono::Utc::now() + chrono::Duration::minutes(5);
-            if let Err(_) = state.db.execute(
-                "INSERT INTO email_auth (short_key, code, expires_at) VALUES (?1, ?2, ?3)",
-                (&short_key, &code, expires_at.naive_utc().to_string()),
-            ) {
-                return (StatusCode::INTERNAL_SERVER_ERROR, "저장 실패").into_response();
-            }
-            tokio::spawn(async move {
-                if let Err(e) = send_email(email_address, code).await {
-                    println!("이메일 전송 실패: {}", e);
-                }
+    // hashed_value 로 이미 있으면 그걸 그대로 반환 (API 키 요청은 같은 소유자의 URL 만)
+    // 조회에 실패하면 새로 만들지 않고 오류로 응답 (중복 생성 방지)
+    // API 키 요청은 다른 소유자의 같은 URL 과 무관하게 자신의 URL 만 찾음
+    let existing = match &api_key {
+        Some(_) => {
+            state
+                .urls
+                .find_by_hashed_value_for_owner(&hashed_value, &email)
+                .await?
+        }
+        None => state.urls.find_by_hashed_value(&hashed_value).await?,
+    };
+    if let Some(url) = existing {
+        let short_key = url.short_key();
+        if url.is_verified {
+            // 이미 인증된 URL 은 오류 응답의 details 로 기존 정보를 알려줌
+            return Err(AppError::ExistingUrl {
+                short_url: short_url(&short_key),
+                app_url: app_url(&short_key, &url.ios_deep_link, &url.android_deep_link),
+                short_key,
             });
-            let response = CreateUrlResponse {is_created: false};
-            return (StatusCode::CREATED, Json(response)).into_response();
         }
-        Err(_) => (),
+        if api_key.is_some() {
+            // 소유자가 API 키로 다시 요청하면 인증 대기 중인 URL 을 바로 활성화
+            state.urls.mark_verified(url.id, &url.random_key).await?;
+            let response = CreateUrlResponse {
+                is_created: false,
+                short_url: short_url(&short_key),
+                app_url: app_url(&short_key, &url.ios_deep_link, &url.android_deep_link),
+                short_key,
+                is_verified: true,
+                verification_expires_at: None,
+                webhook_secret: None,
+            };
+            return Ok((StatusCode::CREATED, Json(response)).into_response());
+        }
+        // 이메일 인증 테이블에 추가
+        let code = generate_random_string(8);
+        let expires_at = insert_email_auth(&state, &short_key, &code).await?;
+        tokio::spawn(async move {
+            if let Err(e) = send_email(url.email, code).await {
+                println!("이메일 전송 실패: {}", e);
+            }
+        });
+        let response = CreateUrlResponse {
+            is_created: false,
+            short_url: short_url(&short_key),
+            app_url: app_url(&short_key, &url.ios_deep_link, &url.android_deep_link),
+            short_key,
+            is_verified: false,
+            verification_expires_at: Some(expires_at),
+            webhook_secret: None,
+        };
+        return Ok((StatusCode::CREATED, Json(response)).into_response());
     }
     // 기존 URL이 없는 경우 새로 생성
-    match state.db.query_row(
-        "INSERT INTO urls (random_key, email, ios_deep_link, ios_fallback_url, android_deep_link, android_fallback_url, default_fallback_url, hashed_value, webhook_url, head_html) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10) RETURNING id",
-        (&random_key, &payload.email, &payload.ios_deep_link, &payload.ios_fallback_url, &payload.android_deep_link, &payload.android_fallback_url, &payload.default_fallback_url, &hashed_value, &payload.webhook_url, &payload.head_html),
-        |row| row.get::<_, i64>(0),
-    ) {
-        Ok(id) => {
-            let unique_key = id_to_key(id);
-            // 캐시에 추가
-            let short_key = (&random_key[..2]).to_string() + &unique_key + &random_key[2..];
-            let code = generate_random_string(8);
-            let expires_at = chrono::Utc::now() + chrono::Duration::minutes(5);
-            if let Err(_) = state.db.execute(
-                "INSERT INTO email_auth (short_key, code, expires_at) VALUES (?1, ?2, ?3)",
-                (&short_key, &code, expires_at.naive_utc().to_string()),
-            ) {
-                return (StatusCode::INTERNAL_SERVER_ERROR, "저장 실패").into_response();
+    let webhook_secret = generate_webhook_secret();
+    let new_url = NewUrl {
+        random_key: random_key.clone(),
+        email: email.clone(),
+        ios_deep_link: payload.ios_deep_link.clone(),
+        ios_fallback_url: payload.ios_fallback_url.clone(),
+        android_deep_link: payload.android_deep_link.clone(),
+        android_fallback_url: payload.android_fallback_url.clone(),
+        android_package_name: payload.android_package_name.clone(),
+        default_fallback_url: payload.default_fallback_url.clone(),
+        hashed_value,
+        webhook_url: payload.webhook_url.clone(),
+        head_html: sanitize_head_html(&payload.head_html),
+        og_title: payload.open_graph.title.clone().unwrap_or_default(),
+        og_description: payload.open_graph.description.clone().unwrap_or_default(),
+        og_image: payload.open_graph.image.clone().unwrap_or_default(),
+        twitter_card: payload.open_graph.twitter_card.clone().unwrap_or_default(),
+        redirect_status,
+        is_verified: api_key.is_some(),
+        webhook_secret: webhook_secret.clone(),
+        activates_at: payload.activates_at,
+        expires_at: payload.expires_at,
+        max_clicks: payload.max_clicks,
+        expired_action,
+        expired_fallback_url,
+        password_hash,
+        alias: payload.alias.clone(),
+    };
+    // 중복 검사 이후 같은 별칭으로 먼저 생성된 경우
+    let id = state.urls.create(new_url).await.map_err(|e| {
+        if payload.alias.is_some() && e.is_unique_violation() {
+            AppError::Conflict("이미 사용 중인 별칭입니다")
+        } else {
+            AppError::from(e)
+        }
+    })?;
+    let short_key = match &payload.alias {
+        Some(alias) => alias.clone(),
+        None => assign_short_key(&state, id, &random_key)
+            .await?
+            .ok_or_else(|| AppError::Internal("단축 키 발급 실패".to_string()))?,
+    };
+
+    // 익명 요청은 이메일 인증 코드 발송
+    let mut verification = None;
+    if api_key.is_none() {
+        let code = generate_random_string(8);
+        let expires_at = insert_email_auth(&state, &short_key, &code).await?;
+        verification = Some((code, expires_at));
+    }
+    let verification_expires_at = verification.as_ref().map(|(_, expires_at)| *expires_at);
+
+    tokio::spawn(async move {
+        if let Some((code, _)) = verification {
+            if let Err(e) = send_email(email, code).await {
+                println!("이메일 전송 실패: {}", e);
             }
-            tokio::spawn(async move {
-                // 클론된 state 사용
-                let state = state_clone.lock().await;
-                
-                if let Err(e) = send_email(payload.email, code).await {
-                    println!("이메일 전송 실패: {}", e);
-                }
+        }
 
-                if payload.head_html.is_empty() {
-                    let client = reqwest::Client::new();
-                    match client.get(&payload.default_fallback_url).send().await {
-                        Ok(response) => {
-                            if let Ok(html) = response.text().await {
-                                let head_html = extract_head_html(&html);
-                                let _ = state.db.execute(
-                                    "UPDATE urls SET head_html = ?1 WHERE id = ?2",
-                                    (&head_html, &id)
-                                );
-                            }
-                        }
-                        Err(e) => println!("헤드 HTML 가져오기 실패: {}", e),
-                    }
+        if payload.head_html.is_empty() {
+            match extract_head_html(&payload.default_fallback_url).await {
+                Ok(head_html) => {
+                    let head_html = sanitize_head_html(&head_html);
+                    let _ = state.urls.update_head_html(id, &head_html).await;
                 }
-            });
-            let response = CreateUrlResponse {
-                is_created: true,
-            };
-            (StatusCode::CREATED, Json(response)).into_response()
+                Err(e) => println!("헤드 HTML 가져오기 실패: {}", e),
+            }
+        }
+    });
+    let response = CreateUrlResponse {
+        is_created: true,
+        short_url: short_url(&short_key),
+        app_url: app_url(
+            &short_key,
+            &payload.ios_deep_link,
+            &payload.android_deep_link,
+        ),
+        short_key,
+        is_verified: api_key.is_some(),
+        verification_expires_at,
+        webhook_secret: Some(webhook_secret),
+    };
+    Ok((StatusCode::CREATED, Json(response)).into_response())
+}
+
+// 이메일 인증 코드 저장 (5분간 유효), 만료 시각 반환
+async fn insert_email_auth(
+    state: &AppState,
+    short_key: &str,
+    code: &str,
+) -> Result<DateTime<Utc>, DbError> {
+    let expires_at = Utc::now() + chrono::Duration::minutes(5);
+    state.email_auth.create(short_key, code, expires_at).await?;
+    Ok(expires_at)
+}
+
+// short_key 로 삭제되지 않은 URL 조회
+// 별칭, 발급한 키 순으로 찾고, 없으면 이전 버전에서 생성된 링크의 키로 해석
+// 앞에 0 에 해당하는 'a' 를 붙인 키도 같은 id 로 해석되므로, 발급한 키와 정확히 같은 키만 허용
+// (다른 표기로 캐시된 항목이 수정/삭제 후에도 남지 않도록)
+pub async fn find_by_short_key(state: &AppState, short_key: &str) -> Result<Option<Url>, DbError> {
+    if validate_alias(short_key).is_ok() {
+        if let Some(url) = state.urls.find_by_alias(short_key).await? {
+            return Ok(Some(url));
+        }
+    }
+    if let Some(url) = state.urls.find_by_generated_key(short_key).await? {
+        return Ok(Some(url));
+    }
+    // 이전 버전에서 생성된 링크는 키에서 id 와 random_key 를 해석
+    // 형식이 잘못된 키는 존재하지 않는 URL 로 취급 (404)
+    let Ok((id, _)) = split_short_key(short_key) else {
+        return Ok(None);
+    };
+    // 발급한 키가 따로 저장된 링크는 이전 방식의 키로 접속할 수 없음
+    Ok(state.urls.find(id).await?.filter(|url| {
+        url.generated_key.is_none() && make_short_key(url.id, &url.random_key) == short_key
+    }))
+}
+
+// API 키 소유자의 URL 조회
+// 다른 사람의 URL 은 존재 여부도 알 수 없도록 None 으로 반환
+pub async fn find_owned_url(
+    state: &AppState,
+    short_key: &str,
+    api_key: &ApiKey,
+) -> Result<Option<Url>, DbError> {
+    Ok(find_by_short_key(state, short_key)
+        .await?
+        .filter(|url| url.email == api_key.owner_email))
+}
+
+// URL 조회 핸들러
+pub async fn get_url_handler(
+    State(state): State<Arc<AppState>>,
+    Path(short_key): Path<String>,
+    api_key: Option<Extension<ApiKey>>,
+) -> Result<Response, AppError> {
+    let Some(Extension(api_key)) = api_key else {
+        return Err(AppError::Unauthorized("API 키가 필요합니다"));
+    };
+    let url = find_owned_url(&state, &short_key, &api_key)
+        .await?
+        .ok_or(AppError::NotFound("URL을 찾을 수 없습니다"))?;
+    Ok((StatusCode::OK, Json(UrlResponse::from(url))).into_response())
+}
+
+// URL 목록 조회 핸들러 (API 키 소유자의 URL 만)
+pub async fn list_urls_handler(
+    State(state): State<Arc<AppState>>,
+    api_key: Option<Extension<ApiKey>>,
+    ApiQuery(query): ApiQuery<ListUrlsQuery>,
+) -> Result<Response, AppError> {
+    let Some(Extension(api_key)) = api_key else {
+        return Err(AppError::Unauthorized("API 키가 필요합니다"));
+    };
+    let page = query.page.unwrap_or(1).max(1);
+    let size = query.size.unwrap_or(20).clamp(1, 100);
+    let email = Some(api_key.owner_email.as_str());
+    let Some(offset) = (page - 1).checked_mul(size) else {
+        return Err(AppError::invalid_field("page", "page 값이 너무 큽니다"));
+    };
+
+    let urls = state.urls.list(email, size, offset).await?;
+    let total = state.urls.count(email).await?;
+    let response = ListUrlsResponse {
+        items: urls.into_iter().map(UrlResponse::from).collect(),
+        page,
+        size,
+        total,
+    };
+    Ok((StatusCode::OK, Json(response)).into_response())
+}
+
+// URL 수정 핸들러
+pub async fn update_url_handler(
+    State(state): State<Arc<AppState>>,
+    Path(short_key): Path<String>,
+    api_key: Option<Extension<ApiKey>>,
+    ApiJson(payload): ApiJson<UpdateUrlRequest>,
+) -> Result<Response, AppError> {
+    let Some(Extension(api_key)) = api_key else {
+        return Err(AppError::Unauthorized("API 키가 필요합니다"));
+    };
+    let url = find_owned_url(&state, &short_key, &api_key)
+        .await?
+        .ok_or(AppError::NotFound("URL을 찾을 수 없습니다"))?;
+    let short_keys = url.short_keys();
+
+    // 안전성 검사는 바뀐 목적지만 다시 실행 (차단 목록이 바뀌어도 다른 필드는 수정할 수 있도록)
+    let changed_destinations: Vec<(&'static str, String)> = [
+        (
+            "defaultFallbackUrl",
+            &payload.default_fallback_url,
+            &url.default_fallback_url,
+        ),
+        (
+            "iosFallbackUrl",
+            &payload.ios_fallback_url,
+            &url.ios_fallback_url,
+        ),
+        (
+            "androidFallbackUrl",
+            &payload.android_fallback_url,
+            &url.android_fallback_url,
+        ),
+        (
+            "expiredFallbackUrl",
+            &payload.expired_fallback_url,
+            &url.expired_fallback_url,
+        ),
+        ("iosDeepLink", &payload.ios_deep_link, &url.ios_deep_link),
+        (
+            "androidDeepLink",
+            &payload.android_deep_link,
+            &url.android_deep_link,
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(field, new, old)| {
+        new.as_ref()
+            .filter(|new| *new != old)
+            .map(|new| (field, new.clone()))
+    })
+    .collect();
+
+    // 전달되지 않은 필드는 기존 값 유지
+    let ios_deep_link = payload.ios_deep_link.unwrap_or(url.ios_deep_link);
+    let ios_fallback_url = payload.ios_fallback_url.unwrap_or(url.ios_fallback_url);
+    let android_deep_link = payload.android_deep_link.unwrap_or(url.android_deep_link);
+    let android_fallback_url = payload
+        .android_fallback_url
+        .unwrap_or(url.android_fallback_url);
+    let android_package_name = payload
+        .android_package_name
+        .unwrap_or(url.android_package_name);
+    let default_fallback_url = payload
+        .default_fallback_url
+        .unwrap_or(url.default_fallback_url);
+    // 웹훅 정책은 주소가 바뀔 때만 다시 검사 (기존 주소는 전송할 때 검사)
+    let webhook_url_changed = payload
+        .webhook_url
+        .as_ref()
+        .is_some_and(|webhook_url| *webhook_url != url.webhook_url);
+    let webhook_url = payload.webhook_url.unwrap_or(url.webhook_url);
+    let head_html = sanitize_head_html(&payload.head_html.unwrap_or(url.head_html));
+    let open_graph = payload.open_graph.unwrap_or_default();
+    let og_title = open_graph.title.unwrap_or(url.og_title);
+    let og_description = open_graph.description.unwrap_or(url.og_description);
+    let og_image = open_graph.image.unwrap_or(url.og_image);
+    let twitter_card = open_graph.twitter_card.unwrap_or(url.twitter_card);
+    let redirect_status = payload.redirect_status.unwrap_or(url.redirect_status);
+    // null 을 보내면 제한 해제
+    let activates_at = payload.activates_at.unwrap_or(url.activates_at);
+    let expires_at = payload.expires_at.unwrap_or(url.expires_at);
+    let max_clicks = payload.max_clicks.unwrap_or(url.max_clicks);
+    let expired_fallback_url = payload
+        .expired_fallback_url
+        .unwrap_or(url.expired_fallback_url);
+    let expired_action = payload.expired_action.unwrap_or(url.expired_action);
+    let password_protected = match &payload.password {
+        None => url.password_hash.is_some(),
+        Some(password) => !password.is_empty(),
+    };
+
+    // 유효성 검사 (수정 후의 값 전체를 검사해 오류를 한 번에 반환)
+    let mut errors = FieldErrors::new();
+    errors.check("iosDeepLink", validate_deep_link(&ios_deep_link));
+    errors.check("iosFallbackUrl", validate_fallback_url(&ios_fallback_url));
+    errors.check("androidDeepLink", validate_deep_link(&android_deep_link));
+    errors.check(
+        "androidFallbackUrl",
+        validate_fallback_url(&android_fallback_url),
+    );
+    errors.check(
+        "androidPackageName",
+        validate_android_package_name(&android_package_name),
+    );
+    errors.check("defaultFallbackUrl", validate_url(&default_fallback_url));
+    errors.check("webhookUrl", validate_webhook_url(&webhook_url));
+    errors.check("headHtml", validate_head_html(&head_html));
+    errors.check("redirectStatus", validate_redirect_status(redirect_status));
+    validate_open_graph(
+        &mut errors,
+        &og_title,
+        &og_description,
+        &og_image,
+        &twitter_card,
+    );
+    validate_link_limits(
+        &mut errors,
+        activates_at,
+        expires_at,
+        max_clicks,
+        &expired_action,
+        &expired_fallback_url,
+    );
+    errors.check(
+        "redirectStatus",
+        validate_permanent_redirect(
+            redirect_status,
+            activates_at,
+            expires_at,
+            max_clicks,
+            password_protected,
+        ),
+    );
+    // 기존 만료 시각은 지났어도 다른 필드를 수정할 수 있도록 새로 보낸 값만 검사
+    if let Some(new_expires_at) = payload.expires_at {
+        errors.check("expiresAt", validate_expires_at(new_expires_at, Utc::now()));
+    }
+    errors.check(
+        "password",
+        validate_password(payload.password.as_deref().unwrap_or("")),
+    );
+    errors.into_result().map_err(AppError::Validation)?;
+    if webhook_url_changed {
+        check_webhook_policy(&state, &webhook_url).await?;
+    }
+    let changed_destinations: Vec<(&'static str, &str)> = changed_destinations
+        .iter()
+        .map(|(field, url)| (*field, url.as_str()))
+        .collect();
+    state
+        .url_safety
+        .check(&web_destinations(&changed_destinations))
+        .await?;
+    let password_hash = match payload.password {
+        None => url.password_hash,
+        Some(password) if password.is_empty() => None,
+        Some(password) => Some(
+            hash_link_password(password)
+                .await
+                .map_err(AppError::Internal)?,
+        ),
+    };
+
+    let update = UrlUpdate {
+        hashed_value: hash_destinations(
+            &ios_deep_link,
+            &ios_fallback_url,
+            &android_deep_link,
+            &android_fallback_url,
+            &default_fallback_url,
+            &(limits_key(
+                activates_at,
+                expires_at,
+                max_clicks,
+                &expired_action,
+                &expired_fallback_url,
+            ) + &redirect_status_key(redirect_status)
+                + &package_key(&android_package_name)
+                + &open_graph_key(&og_title, &og_description, &og_image, &twitter_card)
+                + &identity_key(
+                    password_hash.is_some(),
+                    &url.random_key,
+                    url.alias.as_deref(),
+                )),
+        ),
+        ios_deep_link,
+        ios_fallback_url,
+        android_deep_link,
+        android_fallback_url,
+        android_package_name,
+        default_fallback_url,
+        webhook_url,
+        head_html,
+        og_title,
+        og_description,
+        og_image,
+        twitter_card,
+        redirect_status,
+        activates_at,
+        expires_at,
+        max_clicks,
+        expired_action,
+        expired_fallback_url,
+        password_hash,
+    };
+    state.urls.update(url.id, update).await?;
+    // 리다이렉션에 바로 반영되도록 캐시 제거
+    invalidate_cache(&state, &short_keys);
+
+    let url = find_by_short_key(&state, &short_key)
+        .await?
+        .ok_or(AppError::NotFound("URL을 찾을 수 없습니다"))?;
+    Ok((StatusCode::OK, Json(UrlResponse::from(url))).into_response())
+}
+
+// URL 삭제 핸들러 (소프트 삭제)
+pub async fn delete_url_handler(
+    State(state): State<Arc<AppState>>,
+    Path(short_key): Path<String>,
+    api_key: Option<Extension<ApiKey>>,
+) -> Result<Response, AppError> {
+    let Some(Extension(api_key)) = api_key else {
+        return Err(AppError::Unauthorized("API 키가 필요합니다"));
+    };
+    let url = find_owned_url(&state, &short_key, &api_key)
+        .await?
+        .ok_or(AppError::NotFound("URL을 찾을 수 없습니다"))?;
+    let short_keys = url.short_keys();
+    state.urls.soft_delete(url.id).await?;
+    // 리다이렉션에 바로 반영되도록 캐시 제거
+    invalidate_cache(&state, &short_keys);
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::repository::Database;
+    use crate::utils::app_links::AppLinksConfig;
+    use crate::utils::client_ip::trusted_proxies_from_env;
+    use crate::utils::key_codec::key_codec_from_env;
+    use crate::utils::url_safety::{BlocklistCheck, UrlSafety};
+    use crate::utils::webhook_policy::WebhookPolicy;
+    use dashmap::DashMap;
+    use serde_json::json;
+    use tokio::sync::Notify;
+
+    // 테스트마다 임시 SQLite 파일을 만들어 상태를 구성
+    async fn test_state(name: &str, url_safety: UrlSafety) -> (Arc<AppState>, String) {
+        let path = std::env::temp_dir().join(format!("{}-{}.db", name, std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let db = Database::open(&path).await.unwrap();
+        db.migrate().await.unwrap();
+        let repositories = db.repositories();
+        let state = Arc::new(AppState {
+            urls: repositories.urls,
+            email_auth: repositories.email_auth,
+            api_keys: repositories.api_keys,
+            webhooks: repositories.webhooks,
+            clicks: repositories.clicks,
+            webhook_notify: Notify::new(),
+            cache: DashMap::new(),
+            trusted_proxies: trusted_proxies_from_env(),
+            cookie_secret: "cookie".to_string(),
+            visitor_secret: "visitor".to_string(),
+            password_attempts: DashMap::new(),
+            key_codec: key_codec_from_env(),
+            app_links: AppLinksConfig::from_env(),
+            webhook_policy: WebhookPolicy::from_env(),
+            url_safety,
+        });
+        (state, path)
+    }
+
+    fn remove_db(path: &str) {
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path, suffix));
+        }
+    }
+
+    // headHtml 을 채워 생성 후 외부 페이지를 가져오지 않도록 함
+    fn request(extra: serde_json::Value) -> CreateUrlRequest {
+        let mut payload = json!({
+            "iosDeepLink": "",
+            "iosFallbackUrl": "",
+            "androidDeepLink": "",
+            "androidFallbackUrl": "",
+            "defaultFallbackUrl": "https://example.com/page",
+            "webhookUrl": "",
+            "headHtml": "<title>page</title>",
+        });
+        payload
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(payload).unwrap()
+    }
+
+    async fn create(
+        state: &Arc<AppState>,
+        owner_email: &str,
+        payload: CreateUrlRequest,
+    ) -> Result<Response, AppError> {
+        let api_key = ApiKey {
+            owner_email: owner_email.to_string(),
+        };
+        create_short_url_handler(
+            State(state.clone()),
+            Some(Extension(api_key)),
+            ApiJson(payload),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn api_key_duplicates_are_scoped_to_owner() {
+        let (state, path) = test_state("dedup-owner", UrlSafety::new(vec![])).await;
+        for owner in ["a@example.com", "b@example.com"] {
+            let first = create(&state, owner, request(json!({}))).await;
+            assert_eq!(first.unwrap().status(), StatusCode::CREATED);
+            let second = create(&state, owner, request(json!({}))).await;
+            assert!(matches!(second, Err(AppError::ExistingUrl { .. })));
+        }
+        for owner in ["a@example.com", "b@example.com"] {
+            assert_eq!(state.urls.count(Some(owner)).await.unwrap(), 1);
+        }
+        drop(state);
+        remove_db(&path);
+    }
+
+    #[tokio::test]
+    async fn blocklisted_expired_fallback_url_is_unsafe() {
+        let blocklist =
+            std::env::temp_dir().join(format!("blocklist-handler-{}.txt", std::process::id()));
+        std::fs::write(&blocklist, "evil.example.com\n").unwrap();
+        let url_safety = UrlSafety::new(vec![Box::new(BlocklistCheck::new(
+            &blocklist,
+            std::time::Duration::from_secs(60),
+        ))]);
+        let (state, path) = test_state("unsafe-expired", url_safety).await;
+        let cases = [
+            (
+                "expiredFallbackUrl",
+                json!({ "maxClicks": 1, "expiredFallbackUrl": "https://evil.example.com/x" }),
+            ),
+            (
+                "iosDeepLink",
+                json!({ "iosDeepLink": "https://app.evil.example.com/open" }),
+            ),
+        ];
+        for (field, extra) in cases {
+            let error = create(&state, "a@example.com", request(extra))
+                .await
+                .unwrap_err();
+            assert!(matches!(&error, AppError::UnsafeUrl(unsafe_url) if unsafe_url.field == field));
+            let body = axum::body::to_bytes(error.into_response().into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(body["code"], "unsafe_url");
         }
-        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, "저장 실패").into_response(),
+        // 앱 스킴 딥링크는 검사하지 않음
+        let created = create(
+            &state,
+            "a@example.com",
+            request(json!({ "iosDeepLink": "evil.example.com://open" })),
+        )
+        .await;
+        assert_eq!(created.unwrap().status(), StatusCode::CREATED);
+        assert_eq!(state.urls.count(Some("a@example.com")).await.unwrap(), 1);
+        drop(state);
+        remove_db(&path);
+        let _ = std::fs::remove_file(&blocklist);
     }
 }
@@ -0,0 +1,175 @@
+use crate::error::AppError;
+use crate::extractors::ApiQuery;
+use crate::handlers::short_url_handlers::find_owned_url;
+use crate::models::click_event::ClickInterval;
+use crate::models::repository::ApiKey;
+use crate::schemas::stats_schemas::{
+    ClickBucketResponse, ClickStatsQuery, ClickStatsResponse, PlatformBreakdown, RefererCount,
+};
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+
+// 한 번에 조회할 수 있는 최대 구간 수
+const MAX_BUCKETS: i64 = 1000;
+const TOP_REFERERS: i64 = 10;
+
+// 조회 시작 시각, 집계 단위, 구간 시작 시각 목록
+#[derive(Debug)]
+struct ClickRange {
+    from: DateTime<Utc>,
+    interval: ClickInterval,
+    bucket_starts: Vec<DateTime<Utc>>,
+}
+
+// 날짜 범위 끝의 시각이라 계산이 넘치면 패닉 대신 422 로 응답
+fn click_range(
+    from: Option<DateTime<Utc>>,
+    to: DateTime<Utc>,
+    interval: Option<ClickInterval>,
+) -> Result<ClickRange, AppError> {
+    let out_of_range = || AppError::invalid_field("to", "지원하지 않는 날짜 범위입니다");
+    let from = match from {
+        Some(from) => from,
+        None => to
+            .checked_sub_signed(Duration::days(7))
+            .ok_or_else(out_of_range)?,
+    };
+    if from >= to {
+        return Err(AppError::invalid_field(
+            "from",
+            "from 은 to 보다 이전이어야 합니다",
+        ));
+    }
+    // 이틀 이하면 시간 단위, 그 외에는 일 단위가 기본값
+    let interval = interval.unwrap_or(if to - from <= Duration::days(2) {
+        ClickInterval::Hour
+    } else {
+        ClickInterval::Day
+    });
+    let bucket_from = interval.truncate(from);
+    let buckets = (to - bucket_from).num_seconds() / interval.step().num_seconds() + 1;
+    if buckets > MAX_BUCKETS {
+        return Err(AppError::invalid_field("from", "조회 기간이 너무 깁니다"));
+    }
+
+    let mut bucket_starts = Vec::new();
+    let mut bucket_start = bucket_from;
+    while bucket_start < to {
+        bucket_starts.push(bucket_start);
+        bucket_start = bucket_start
+            .checked_add_signed(interval.step())
+            .ok_or_else(out_of_range)?;
+    }
+    Ok(ClickRange {
+        from,
+        interval,
+        bucket_starts,
+    })
+}
+
+// 접속 통계 조회 핸들러 (URL 소유자의 API 키로만 조회 가능)
+pub async fn get_url_stats_handler(
+    State(state): State<Arc<AppState>>,
+    Path(short_key): Path<String>,
+    api_key: Option<Extension<ApiKey>>,
+    ApiQuery(query): ApiQuery<ClickStatsQuery>,
+) -> Result<Response, AppError> {
+    let Some(Extension(api_key)) = api_key else {
+        return Err(AppError::Unauthorized("API 키가 필요합니다"));
+    };
+    let url = find_owned_url(&state, &short_key, &api_key)
+        .await?
+        .ok_or(AppError::NotFound("URL을 찾을 수 없습니다"))?;
+
+    let to = query.to.unwrap_or_else(Utc::now);
+    let ClickRange {
+        from,
+        interval,
+        bucket_starts,
+    } = click_range(query.from, to, query.interval)?;
+    let bucket_from = bucket_starts[0];
+
+    let (total_clicks, unique_clicks) = state.clicks.count(url.id, from, to).await?;
+    let platforms = state.clicks.count_by_platform(url.id, from, to).await?;
+    let referers = state
+        .clicks
+        .top_referers(url.id, from, to, TOP_REFERERS)
+        .await?;
+    let series = state
+        .clicks
+        .time_series(url.id, interval, bucket_from, to)
+        .await?;
+
+    let mut breakdown = PlatformBreakdown::default();
+    for (platform, clicks) in platforms {
+        match platform.as_str() {
+            "ios" => breakdown.ios += clicks,
+            "android" => breakdown.android += clicks,
+            _ => breakdown.other += clicks,
+        }
+    }
+
+    // 접속이 없던 구간도 0 으로 채워 연속된 시계열로 반환
+    let mut series = series.into_iter().peekable();
+    let time_series = bucket_starts
+        .into_iter()
+        .map(|bucket_start| {
+            let bucket = series.next_if(|bucket| bucket.bucket_start == bucket_start);
+            ClickBucketResponse {
+                bucket_start,
+                clicks: bucket.as_ref().map_or(0, |bucket| bucket.clicks),
+                unique_clicks: bucket.as_ref().map_or(0, |bucket| bucket.unique_clicks),
+            }
+        })
+        .collect();
+
+    let response = ClickStatsResponse {
+        short_key,
+        from,
+        to,
+        interval,
+        total_clicks,
+        unique_clicks,
+        platforms: breakdown,
+        top_referers: referers
+            .into_iter()
+            .map(|(referer, clicks)| RefererCount { referer, clicks })
+            .collect(),
+        time_series,
+    };
+    Ok((StatusCode::OK, Json(response)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extreme_to_is_rejected_instead_of_panicking() {
+        // 기본 시작 시각 (to - 7일) 이 범위를 벗어남
+        let error = click_range(None, DateTime::<Utc>::MIN_UTC, None).unwrap_err();
+        assert_eq!(error.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        // 마지막 구간 다음 시각이 범위를 벗어남
+        let to = DateTime::<Utc>::MAX_UTC;
+        let from = to - Duration::hours(36);
+        let error = click_range(Some(from), to, Some(ClickInterval::Day)).unwrap_err();
+        assert_eq!(error.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[test]
+    fn range_defaults_to_last_week_in_days() {
+        let to = "2024-05-10T12:30:00Z".parse().unwrap();
+        let range = click_range(None, to, None).unwrap();
+        assert_eq!(to - range.from, Duration::days(7));
+        assert_eq!(range.interval, ClickInterval::Day);
+        assert_eq!(range.bucket_starts.len(), 8);
+        assert_eq!(range.bucket_starts[0], range.interval.truncate(range.from));
+    }
+}
@@ -1,55 +1,61 @@
-use crate::utils::converter::split_short_key;
+use crate::error::AppError;
+use crate::handlers::short_url_handlers::find_by_short_key;
+use crate::utils::short_url::short_url;
+use crate::utils::template::render;
 use crate::AppState;
-use axum::{extract::Path, http::StatusCode, response::Html, response::IntoResponse};
-use std::env;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Html,
+    response::{IntoResponse, Response},
+};
 use std::sync::Arc;
-use tokio::sync::Mutex;
 
 pub async fn verify_email_handler(
-    state: axum::extract::State<Arc<Mutex<AppState>>>,
+    State(state): State<Arc<AppState>>,
     Path(code): Path<String>,
-) -> impl IntoResponse {
-    let state = state.lock().await;
-
-    // 검증 코드로 short_key 찾기
-    let short_key = match state.db.query_row(
-        "SELECT short_key FROM email_auth WHERE code = ?1 AND expires_at > datetime('now')",
-        [&code],
-        |row| row.get::<_, String>(0),
-    ) {
-        Ok(key) => key,
-        Err(_) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Html(include_str!("../templates/verify/failed.html")),
-            )
-                .into_response()
+) -> Response {
+    match verify_email(&state, &code).await {
+        Ok(short_key) => {
+            let short_url = short_url(&short_key);
+            let success_html = render(
+                include_str!("../templates/verify/success.html"),
+                &[("short_url", &short_url)],
+            );
+            (StatusCode::OK, Html(success_html)).into_response()
         }
-    };
+        Err(e) => failed_page(e),
+    }
+}
 
-    let (url_id, random_key) = split_short_key(&short_key);
+// 인증 코드로 URL 을 활성화하고 short_key 반환
+async fn verify_email(state: &AppState, code: &str) -> Result<String, AppError> {
+    // 없거나 만료된 코드는 404
+    let short_key = state
+        .email_auth
+        .find_short_key(code)
+        .await?
+        .ok_or(AppError::NotFound(
+            "인증 코드가 올바르지 않거나 만료되었습니다",
+        ))?;
+    // 키 형식이 잘못됐거나 삭제된 URL 이면 404
+    let url = find_by_short_key(state, &short_key)
+        .await?
+        .ok_or(AppError::NotFound("URL을 찾을 수 없습니다"))?;
+    state.urls.mark_verified(url.id, &url.random_key).await?;
+    // 검증 완료된 코드 삭제
+    let _ = state.email_auth.delete(code).await;
+    Ok(short_key)
+}
 
-    // URL 검증 상태 업데이트
-    match state.db.execute(
-        "UPDATE urls SET is_verified = true WHERE random_key = ?1 AND id = ?2",
-        [random_key.as_str(), &url_id.to_string()],
-    ) {
-        Ok(_) => {
-            // 검증 완료된 코드 삭제
-            let host = env::var("SERVER_HOST").unwrap_or("127.0.0.1".to_string());
-            let port = env::var("SERVER_PORT").unwrap_or("3000".to_string());
-            let short_url = format!("http://{}:{}/{}", host, port, short_key);
-            let _ = state
-                .db
-                .execute("DELETE FROM email_auth WHERE code = ?1", [&code]);
-            let success_html =
-                include_str!("../templates/verify/success.html").replace("{short_url}", &short_url);
-            (StatusCode::OK, Html(success_html)).into_response()
+// 인증 실패 페이지 (잘못된 코드는 실패 안내, 서버 오류는 오류 안내)
+fn failed_page(error: AppError) -> Response {
+    let page = match error {
+        AppError::NotFound(_) => include_str!("../templates/verify/failed.html"),
+        _ => {
+            println!("이메일 인증 실패: {}", error);
+            include_str!("../templates/verify/error.html")
         }
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Html(include_str!("../templates/verify/error.html")),
-        )
-            .into_response(),
-    }
+    };
+    (error.status(), Html(page)).into_response()
 }
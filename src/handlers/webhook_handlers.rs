@@ -0,0 +1,48 @@
+use crate::error::AppError;
+use crate::extractors::ApiQuery;
+use crate::handlers::short_url_handlers::find_owned_url;
+use crate::models::repository::ApiKey;
+use crate::schemas::webhook_schemas::{
+    ListWebhookDeliveriesQuery, ListWebhookDeliveriesResponse, WebhookDeliveryResponse,
+};
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use std::sync::Arc;
+
+// 최종 실패한 웹훅 전송 내역 조회 핸들러 (방문자 정보가 포함되므로 URL 소유자만)
+pub async fn list_failed_webhooks_handler(
+    State(state): State<Arc<AppState>>,
+    Path(short_key): Path<String>,
+    api_key: Option<Extension<ApiKey>>,
+    ApiQuery(query): ApiQuery<ListWebhookDeliveriesQuery>,
+) -> Result<Response, AppError> {
+    let Some(Extension(api_key)) = api_key else {
+        return Err(AppError::Unauthorized("API 키가 필요합니다"));
+    };
+    let url = find_owned_url(&state, &short_key, &api_key)
+        .await?
+        .ok_or(AppError::NotFound("URL을 찾을 수 없습니다"))?;
+    let page = query.page.unwrap_or(1).max(1);
+    let size = query.size.unwrap_or(20).clamp(1, 100);
+    let Some(offset) = (page - 1).checked_mul(size) else {
+        return Err(AppError::invalid_field("page", "page 값이 너무 큽니다"));
+    };
+
+    let deliveries = state.webhooks.list_failed(url.id, size, offset).await?;
+    let total = state.webhooks.count_failed(url.id).await?;
+    let response = ListWebhookDeliveriesResponse {
+        items: deliveries
+            .into_iter()
+            .map(WebhookDeliveryResponse::from)
+            .collect(),
+        page,
+        size,
+        total,
+    };
+    Ok((StatusCode::OK, Json(response)).into_response())
+}
@@ -0,0 +1,85 @@
+use crate::error::AppError;
+use crate::schemas::well_known_schemas::{
+    AppLinks, AppLinksComponent, AppLinksDetail, AppleAppSiteAssociation, AssetLink,
+    AssetLinkTarget,
+};
+use crate::AppState;
+use axum::{
+    extract::State,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::sync::Arc;
+
+// 앱으로 열리는 경로 (/app/{단축 키})
+// 앱으로 열리면 서버를 거치지 않아 만료, 접속 횟수, 비밀번호, 통계, 웹훅이 적용되지 않으므로
+// 일반 단축 링크(/{단축 키})는 항상 서버에서 처리
+const APP_LINK_PATH: &str = "/app/*";
+
+// iOS Universal Links 용 apple-app-site-association
+// 리다이렉트 없이 application/json 으로 바로 응답해야 함
+pub async fn apple_app_site_association_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<Response, AppError> {
+    let app_ids = &state.app_links.apple_app_ids;
+    if app_ids.is_empty() {
+        return Err(AppError::NotFound("설정된 iOS 앱이 없습니다"));
+    }
+    Ok(Json(app_site_association(app_ids)).into_response())
+}
+
+fn app_site_association(app_ids: &[String]) -> AppleAppSiteAssociation {
+    AppleAppSiteAssociation {
+        applinks: AppLinks {
+            details: vec![AppLinksDetail {
+                app_ids: app_ids.to_vec(),
+                components: vec![AppLinksComponent {
+                    path: APP_LINK_PATH,
+                    exclude: false,
+                }],
+            }],
+        },
+    }
+}
+
+// Android App Links 용 assetlinks.json
+pub async fn asset_links_handler(State(state): State<Arc<AppState>>) -> Result<Response, AppError> {
+    let config = &state.app_links;
+    if config.android_package_names.is_empty() || config.android_fingerprints.is_empty() {
+        return Err(AppError::NotFound("설정된 Android 앱이 없습니다"));
+    }
+    let links: Vec<AssetLink> = config
+        .android_package_names
+        .iter()
+        .map(|package_name| AssetLink {
+            relation: vec!["delegate_permission/common.handle_all_urls"],
+            target: AssetLinkTarget {
+                namespace: "android_app",
+                package_name: package_name.clone(),
+                sha256_cert_fingerprints: config.android_fingerprints.clone(),
+            },
+        })
+        .collect();
+    Ok(Json(links).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_app_paths_open_in_the_app() {
+        let association = app_site_association(&["ABCDE12345.com.example.app".to_string()]);
+        assert_eq!(
+            serde_json::to_value(&association).unwrap(),
+            serde_json::json!({
+                "applinks": {
+                    "details": [{
+                        "appIDs": ["ABCDE12345.com.example.app"],
+                        "components": [{"/": "/app/*"}]
+                    }]
+                }
+            })
+        );
+    }
+}
@@ -1,45 +1,149 @@
+mod error;
+mod extractors;
 mod handlers;
+mod middlewares;
 mod models;
 mod schemas;
 mod state;
 mod utils;
 mod validators;
+mod workers;
 use crate::handlers::{
-    page_handlers::*, redirect_handlers::*, short_url_handlers::*, verify_handlers::*,
+    page_handlers::*, password_handlers::*, redirect_handlers::*, short_url_handlers::*,
+    stats_handlers::*, verify_handlers::*, webhook_handlers::*, well_known_handlers::*,
 };
-use crate::models::migrate::db_init;
+use crate::middlewares::auth_middlewares::{api_key_middleware, hash_api_key};
+use crate::models::migrate::latest_version;
+use crate::models::repository::Database;
 use crate::state::AppState;
+use crate::utils::app_links::AppLinksConfig;
+use crate::utils::client_ip::trusted_proxies_from_env;
+use crate::utils::generator::{generate_api_key, generate_random_string};
+use crate::utils::key_codec::key_codec_from_env;
+use crate::utils::url_safety::UrlSafety;
+use crate::utils::webhook_policy::WebhookPolicy;
+use crate::workers::webhook_worker::{run_webhook_worker, WebhookConfig};
 use axum::{
+    middleware,
     routing::{get, post},
     Router,
 };
+use dashmap::DashMap;
 use dotenv::dotenv;
-use rusqlite::Connection;
-use std::collections::HashMap;
 use std::env;
+use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use tokio::sync::RwLock;
+use tokio::sync::Notify;
 
 #[tokio::main]
-async fn main() -> Result<(), rusqlite::Error> {
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv().ok();
     // DB 초기화
-    let db = Connection::open("sqlite3.db")?;
-    db_init(&db)?;
+    let database_url = env::var("DATABASE_URL").unwrap_or("sqlite3.db".to_string());
+    let database = Database::open(&database_url).await?;
 
-    let state = Arc::new(Mutex::new(AppState {
-        db,
-        cache: RwLock::new(HashMap::new()),
-    }));
+    // 마이그레이션 (`url-shortener migrate` 로 실행하면 적용만 하고 종료)
+    let applied = match database.migrate().await {
+        Ok(applied) => applied,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    for version in &applied {
+        println!("Applied migration {:04}", version);
+    }
+    let repositories = database.repositories();
+
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("migrate") => {
+            println!("Schema is at version {}", latest_version());
+            return Ok(());
+        }
+        // API 키 발급 (`url-shortener create-api-key <email> [name]`), 키 원문은 이때만 출력
+        Some("create-api-key") => {
+            let Some(owner_email) = args.get(2) else {
+                eprintln!("usage: url-shortener create-api-key <email> [name]");
+                std::process::exit(1);
+            };
+            let name = args.get(3).map(String::as_str).unwrap_or("default");
+            let api_key = generate_api_key();
+            repositories
+                .api_keys
+                .create(name, owner_email, &hash_api_key(&api_key))
+                .await?;
+            println!("{}", api_key);
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    // 지정하지 않으면 재시작할 때마다 잠금 해제 쿠키가 무효가 됨
+    let cookie_secret = env::var("COOKIE_SECRET").unwrap_or_else(|_| generate_random_string(32));
+    // 지정하지 않으면 쿠키 서명 키를 사용 (재시작하면 같은 방문자도 새 방문자로 집계될 수 있음)
+    let visitor_secret = env::var("VISITOR_HASH_SECRET").unwrap_or_else(|_| cookie_secret.clone());
+    let state = Arc::new(AppState {
+        urls: repositories.urls,
+        email_auth: repositories.email_auth,
+        api_keys: repositories.api_keys,
+        webhooks: repositories.webhooks,
+        clicks: repositories.clicks,
+        webhook_notify: Notify::new(),
+        cache: DashMap::new(),
+        trusted_proxies: trusted_proxies_from_env(),
+        cookie_secret,
+        visitor_secret,
+        password_attempts: DashMap::new(),
+        key_codec: key_codec_from_env(),
+        app_links: AppLinksConfig::from_env(),
+        webhook_policy: WebhookPolicy::from_env(),
+        url_safety: UrlSafety::from_env(),
+    });
+
+    // 웹훅 전송 워커 시작
+    tokio::spawn(run_webhook_worker(state.clone(), WebhookConfig::from_env()));
 
     // 라우터 설정
+    let api = Router::new()
+        .route(
+            "/v1/urls",
+            post(create_short_url_handler).get(list_urls_handler),
+        )
+        .route(
+            "/v1/urls/:short_key",
+            get(get_url_handler)
+                .patch(update_url_handler)
+                .delete(delete_url_handler),
+        )
+        .route("/v1/urls/:short_key/stats", get(get_url_stats_handler))
+        .route(
+            "/v1/urls/:short_key/webhooks/failed",
+            get(list_failed_webhooks_handler),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            api_key_middleware,
+        ));
     let app = Router::new()
         .route("/", get(index_handler))
         .route("/health", get(health_check))
-        .route("/v1/urls", post(create_short_url_handler))
+        .route(
+            "/.well-known/apple-app-site-association",
+            get(apple_app_site_association_handler),
+        )
+        .route("/.well-known/assetlinks.json", get(asset_links_handler))
+        .merge(api)
         .route("/v1/verify/:code", get(verify_email_handler))
-        .route("/:short_key", get(redirect_to_original_handler))
+        .route(
+            "/:short_key",
+            get(redirect_to_original_handler).post(unlock_link_handler),
+        )
+        // 앱이 설치되지 않았으면 일반 단축 링크와 같이 처리
+        .route(
+            "/app/:short_key",
+            get(redirect_to_original_handler).post(unlock_link_handler),
+        )
         .with_state(state);
 
     // 서버 시작
@@ -49,6 +153,12 @@ async fn main() -> Result<(), rusqlite::Error> {
         .await
         .unwrap();
     println!("Server running on http://{}:{}", host, port);
-    axum::serve(listener, app).await.unwrap();
+    // 클라이언트 IP 확인을 위해 연결 정보를 함께 전달
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
     Ok(())
 }
@@ -0,0 +1,47 @@
+use crate::error::AppError;
+use crate::state::AppState;
+use axum::{
+    extract::{Request, State},
+    http::header::AUTHORIZATION,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+// API 키는 충분히 긴 무작위 값이므로 단순 해시로 저장
+pub fn hash_api_key(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.as_bytes()))
+}
+
+// Authorization: Bearer <API 키> 확인
+// 헤더가 없으면 익명 요청으로 통과시키고, 유효한 키면 ApiKey 를 요청 확장에 추가
+pub async fn api_key_middleware(
+    State(state): State<Arc<AppState>>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let Some(header) = req.headers().get(AUTHORIZATION) else {
+        return next.run(req).await;
+    };
+    let Some(token) = header
+        .to_str()
+        .ok()
+        .and_then(|value| value.strip_prefix("Bearer "))
+    else {
+        return AppError::Unauthorized("인증 헤더 형식이 올바르지 않습니다").into_response();
+    };
+
+    match state
+        .api_keys
+        .find_by_hash(&hash_api_key(token.trim()))
+        .await
+    {
+        Ok(Some(api_key)) => {
+            req.extensions_mut().insert(api_key);
+            next.run(req).await
+        }
+        Ok(None) => AppError::Unauthorized("유효하지 않은 API 키입니다").into_response(),
+        Err(e) => AppError::from(e).into_response(),
+    }
+}
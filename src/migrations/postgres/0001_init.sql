@@ -0,0 +1,23 @@
+CREATE TABLE IF NOT EXISTS urls (
+    id BIGSERIAL PRIMARY KEY,
+    random_key VARCHAR(4) NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    ios_deep_link TEXT NULL,
+    ios_fallback_url TEXT NULL,
+    android_deep_link TEXT NULL,
+    android_fallback_url TEXT NULL,
+    default_fallback_url TEXT NOT NULL,
+    hashed_value TEXT NOT NULL,
+    webhook_url TEXT NULL,
+    head_html TEXT NULL,
+    is_verified BOOLEAN NOT NULL DEFAULT FALSE,
+    is_deleted BOOLEAN NOT NULL DEFAULT FALSE
+);
+CREATE INDEX IF NOT EXISTS idx_hashed_value ON urls (hashed_value);
+CREATE TABLE IF NOT EXISTS email_auth (
+    id BIGSERIAL PRIMARY KEY,
+    short_key VARCHAR(10) NOT NULL,
+    code VARCHAR(8) NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL
+);
+CREATE INDEX IF NOT EXISTS code ON email_auth (code);
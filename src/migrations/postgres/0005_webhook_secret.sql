@@ -0,0 +1 @@
+ALTER TABLE urls ADD COLUMN webhook_secret TEXT NULL;
@@ -0,0 +1,29 @@
+CREATE TABLE clicks (
+    id BIGSERIAL PRIMARY KEY,
+    url_id BIGINT NOT NULL,
+    occurred_at TIMESTAMPTZ NOT NULL,
+    platform VARCHAR(16) NOT NULL,
+    visitor_hash VARCHAR(64) NOT NULL,
+    referer TEXT NULL,
+    accept_language TEXT NULL,
+    query TEXT NULL,
+    destination TEXT NOT NULL
+);
+CREATE INDEX idx_clicks_url_time ON clicks (url_id, occurred_at);
+CREATE INDEX idx_clicks_url_visitor ON clicks (url_id, visitor_hash, occurred_at);
+
+CREATE TABLE click_rollups_hourly (
+    url_id BIGINT NOT NULL,
+    bucket_start TIMESTAMPTZ NOT NULL,
+    clicks BIGINT NOT NULL DEFAULT 0,
+    unique_clicks BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (url_id, bucket_start)
+);
+
+CREATE TABLE click_rollups_daily (
+    url_id BIGINT NOT NULL,
+    bucket_start TIMESTAMPTZ NOT NULL,
+    clicks BIGINT NOT NULL DEFAULT 0,
+    unique_clicks BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (url_id, bucket_start)
+);
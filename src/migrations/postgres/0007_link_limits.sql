@@ -0,0 +1,6 @@
+ALTER TABLE urls ADD COLUMN activates_at TIMESTAMPTZ NULL;
+ALTER TABLE urls ADD COLUMN expires_at TIMESTAMPTZ NULL;
+ALTER TABLE urls ADD COLUMN max_clicks INTEGER NULL;
+ALTER TABLE urls ADD COLUMN click_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE urls ADD COLUMN expired_action VARCHAR(16) NOT NULL DEFAULT 'gone';
+ALTER TABLE urls ADD COLUMN expired_fallback_url TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,4 @@
+ALTER TABLE urls ADD COLUMN alias VARCHAR(64) NULL;
+CREATE UNIQUE INDEX idx_urls_alias ON urls (alias);
+-- 별칭으로도 이메일 인증을 하므로 최대 64자까지 저장
+ALTER TABLE email_auth ALTER COLUMN short_key TYPE VARCHAR(64);
@@ -0,0 +1,4 @@
+ALTER TABLE urls ADD COLUMN og_title VARCHAR(200) NOT NULL DEFAULT '';
+ALTER TABLE urls ADD COLUMN og_description VARCHAR(500) NOT NULL DEFAULT '';
+ALTER TABLE urls ADD COLUMN og_image TEXT NOT NULL DEFAULT '';
+ALTER TABLE urls ADD COLUMN twitter_card VARCHAR(32) NOT NULL DEFAULT '';
@@ -0,0 +1,3 @@
+ALTER TABLE urls ADD COLUMN alias VARCHAR(64) NULL;
+CREATE UNIQUE INDEX idx_urls_alias ON urls (alias);
+-- email_auth.short_key 는 PostgreSQL 에서만 넓힘 (SQLite 는 VARCHAR 길이를 검사하지 않음)
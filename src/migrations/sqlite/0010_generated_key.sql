@@ -0,0 +1,2 @@
+ALTER TABLE urls ADD COLUMN generated_key VARCHAR(64) NULL;
+CREATE UNIQUE INDEX idx_urls_generated_key ON urls (generated_key);
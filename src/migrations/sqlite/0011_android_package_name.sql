@@ -0,0 +1 @@
+ALTER TABLE urls ADD COLUMN android_package_name VARCHAR(255) NOT NULL DEFAULT '';
@@ -0,0 +1,118 @@
+use crate::utils::client_ip::client_ip;
+use crate::utils::platform::{detect_platform, Platform};
+use crate::utils::signature::{hmac_sha256, to_hex};
+use axum::http::HeaderMap;
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+// 단축 URL 접속 이벤트
+// 웹훅 본문과 접속 통계 저장에 같은 값을 사용
+#[derive(Clone, Debug, Serialize)]
+pub struct ClickEvent {
+    #[serde(skip)]
+    pub url_id: i64,
+    pub short_key: String,
+    pub occurred_at: DateTime<Utc>,
+    pub platform: Platform,
+    pub user_agent: String,
+    pub referer: Option<String>,
+    pub accept_language: Option<String>,
+    pub client_ip: IpAddr,
+    // 단축 URL 에 붙어 온 쿼리 문자열 ('?' 제외)
+    pub query: Option<String>,
+    // 접속자를 보낸 곳 (리다이렉트 위치 또는 시도한 딥링크)
+    pub destination: String,
+    // 순 방문자 집계용 식별자 (웹훅 본문에는 포함하지 않음)
+    #[serde(skip)]
+    pub visitor_hash: String,
+}
+
+impl ClickEvent {
+    pub fn from_request(
+        url_id: i64,
+        short_key: &str,
+        headers: &HeaderMap,
+        query: Option<String>,
+        peer: IpAddr,
+        trusted_proxies: &[IpNet],
+        visitor_secret: &str,
+    ) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        let user_agent = header("User-Agent").unwrap_or_default();
+        let client_ip = client_ip(peer, headers, trusted_proxies);
+        ClickEvent {
+            url_id,
+            short_key: short_key.to_string(),
+            occurred_at: Utc::now(),
+            platform: detect_platform(&user_agent),
+            visitor_hash: visitor_hash(visitor_secret, client_ip, &user_agent),
+            user_agent,
+            referer: header("Referer"),
+            accept_language: header("Accept-Language"),
+            client_ip,
+            query: query.filter(|query| !query.is_empty()),
+            destination: String::new(),
+        }
+    }
+}
+
+// HMAC-SHA256(서버 비밀 키, "{ip}|{user_agent}")
+// 비밀 키 없이는 IP 대역을 전수 대입해도 해시에서 IP 를 되찾을 수 없음
+fn visitor_hash(secret: &str, client_ip: IpAddr, user_agent: &str) -> String {
+    to_hex(&hmac_sha256(
+        secret.as_bytes(),
+        format!("{}|{}", client_ip, user_agent).as_bytes(),
+    ))
+}
+
+// 접속 통계 집계 단위
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClickInterval {
+    Hour,
+    Day,
+}
+
+impl ClickInterval {
+    pub fn step(self) -> Duration {
+        match self {
+            ClickInterval::Hour => Duration::hours(1),
+            ClickInterval::Day => Duration::days(1),
+        }
+    }
+
+    // 시각이 속한 구간의 시작 시각 (UTC 기준)
+    pub fn truncate(self, at: DateTime<Utc>) -> DateTime<Utc> {
+        at.duration_trunc(self.step()).unwrap_or(at)
+    }
+
+    pub fn rollup_table(self) -> &'static str {
+        match self {
+            ClickInterval::Hour => "click_rollups_hourly",
+            ClickInterval::Day => "click_rollups_daily",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visitor_hash_depends_on_secret() {
+        let ip: IpAddr = "203.0.113.7".parse().unwrap();
+        let hash = visitor_hash("secret-a", ip, "Mozilla/5.0");
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, visitor_hash("secret-a", ip, "Mozilla/5.0"));
+        assert_ne!(hash, visitor_hash("secret-b", ip, "Mozilla/5.0"));
+        assert_ne!(hash, visitor_hash("secret-a", ip, "curl/8.0"));
+    }
+}
@@ -1,38 +1,348 @@
-use rusqlite::Connection;
-use rusqlite::Result;
-
-pub fn db_init(db: &Connection) -> Result<(), rusqlite::Error> {
-    db.execute(
-        "CREATE TABLE IF NOT EXISTS urls (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            random_key VARCHAR(4) NOT NULL,
-            email VARCHAR(255) NOT NULL,
-            ios_deep_link TEXT NULL,
-            ios_fallback_url TEXT NULL,
-            android_deep_link TEXT NULL,
-            android_fallback_url TEXT NULL,
-            default_fallback_url TEXT NOT NULL,
-            hashed_value TEXT NOT NULL,
-            webhook_url TEXT NULL,
-            head_html TEXT NULL,
-            is_verified INTEGER NOT NULL DEFAULT 0,
-            is_deleted INTEGER NOT NULL DEFAULT 0
-        )",
-        [],
-    )?;
-    db.execute(
-        "CREATE INDEX IF NOT EXISTS idx_hashed_value ON urls (hashed_value)",
-        [],
-    )?;
-    db.execute(
-        "CREATE TABLE IF NOT EXISTS email_auth (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            short_key VARCHAR(10) NOT NULL,
-            code VARCHAR(8) NOT NULL,
-            expires_at DATETIME NOT NULL
+use crate::models::pool::{self, DbPool};
+use crate::models::repository::DbError;
+use rusqlite::{Transaction, TransactionBehavior};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::fmt;
+
+// 번호가 붙은 스키마 마이그레이션
+// 이미 배포된 마이그레이션의 SQL 은 수정하지 말고 항상 새 번호로 추가
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub sqlite: &'static str,
+    pub postgres: &'static str,
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "init",
+        sqlite: include_str!("../migrations/sqlite/0001_init.sql"),
+        postgres: include_str!("../migrations/postgres/0001_init.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "api_keys",
+        sqlite: include_str!("../migrations/sqlite/0002_api_keys.sql"),
+        postgres: include_str!("../migrations/postgres/0002_api_keys.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "redirect_status",
+        sqlite: include_str!("../migrations/sqlite/0003_redirect_status.sql"),
+        postgres: include_str!("../migrations/postgres/0003_redirect_status.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "webhook_deliveries",
+        sqlite: include_str!("../migrations/sqlite/0004_webhook_deliveries.sql"),
+        postgres: include_str!("../migrations/postgres/0004_webhook_deliveries.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "webhook_secret",
+        sqlite: include_str!("../migrations/sqlite/0005_webhook_secret.sql"),
+        postgres: include_str!("../migrations/postgres/0005_webhook_secret.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "clicks",
+        sqlite: include_str!("../migrations/sqlite/0006_clicks.sql"),
+        postgres: include_str!("../migrations/postgres/0006_clicks.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "link_limits",
+        sqlite: include_str!("../migrations/sqlite/0007_link_limits.sql"),
+        postgres: include_str!("../migrations/postgres/0007_link_limits.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "link_password",
+        sqlite: include_str!("../migrations/sqlite/0008_link_password.sql"),
+        postgres: include_str!("../migrations/postgres/0008_link_password.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "url_alias",
+        sqlite: include_str!("../migrations/sqlite/0009_url_alias.sql"),
+        postgres: include_str!("../migrations/postgres/0009_url_alias.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "generated_key",
+        sqlite: include_str!("../migrations/sqlite/0010_generated_key.sql"),
+        postgres: include_str!("../migrations/postgres/0010_generated_key.sql"),
+    },
+    Migration {
+        version: 11,
+        name: "android_package_name",
+        sqlite: include_str!("../migrations/sqlite/0011_android_package_name.sql"),
+        postgres: include_str!("../migrations/postgres/0011_android_package_name.sql"),
+    },
+    Migration {
+        version: 12,
+        name: "open_graph",
+        sqlite: include_str!("../migrations/sqlite/0012_open_graph.sql"),
+        postgres: include_str!("../migrations/postgres/0012_open_graph.sql"),
+    },
+];
+
+#[derive(Debug)]
+pub enum MigrationError {
+    Db(DbError),
+    // 적용된 마이그레이션의 SQL 이 바이너리에 포함된 것과 다름
+    ChecksumMismatch { version: i64, name: String },
+    // DB 스키마가 이 바이너리가 아는 것보다 최신
+    DatabaseNewer { database: i64, binary: i64 },
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::Db(e) => write!(f, "마이그레이션 실패: {}", e),
+            MigrationError::ChecksumMismatch { version, name } => write!(
+                f,
+                "마이그레이션 {:04}_{} 의 체크섬이 일치하지 않습니다",
+                version, name
+            ),
+            MigrationError::DatabaseNewer { database, binary } => write!(
+                f,
+                "DB 스키마 버전({})이 실행 파일이 지원하는 버전({})보다 높습니다",
+                database, binary
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+impl From<DbError> for MigrationError {
+    fn from(e: DbError) -> Self {
+        MigrationError::Db(e)
+    }
+}
+
+impl From<sqlx::Error> for MigrationError {
+    fn from(e: sqlx::Error) -> Self {
+        MigrationError::Db(DbError::Postgres(e))
+    }
+}
+
+fn checksum(sql: &str) -> String {
+    format!("{:x}", Sha256::digest(sql.as_bytes()))
+}
+
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+// 적용 이력을 검증하고 아직 적용되지 않은 마이그레이션 목록을 버전 순서로 반환
+fn pending(
+    migrations: &'static [Migration],
+    applied: &[(i64, String)],
+    sql: fn(&Migration) -> &'static str,
+) -> Result<Vec<&'static Migration>, MigrationError> {
+    let database = applied.iter().map(|(v, _)| *v).max().unwrap_or(0);
+    let binary = migrations.last().map(|m| m.version).unwrap_or(0);
+    if database > binary {
+        return Err(MigrationError::DatabaseNewer { database, binary });
+    }
+    let mut pending = Vec::new();
+    for migration in migrations {
+        match applied.iter().find(|(v, _)| *v == migration.version) {
+            Some((_, applied_checksum)) => {
+                if *applied_checksum != checksum(sql(migration)) {
+                    return Err(MigrationError::ChecksumMismatch {
+                        version: migration.version,
+                        name: migration.name.to_string(),
+                    });
+                }
+            }
+            None => pending.push(migration),
+        }
+    }
+    Ok(pending)
+}
+
+// SQLite 마이그레이션 적용, 새로 적용된 버전 목록 반환
+pub async fn sqlite_apply(db: &DbPool) -> Result<Vec<i64>, MigrationError> {
+    pool::run(db, |db| {
+        db.execute(
+            "CREATE TABLE IF NOT EXISTS schema_version (
+                version INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                checksum TEXT NOT NULL,
+                applied_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )?;
+        // 이력 확인부터 쓰기 잠금을 잡아 동시에 실행된 migrate 가 같은 마이그레이션을 두 번 적용하지 않도록 함
+        let tx = Transaction::new_unchecked(db, TransactionBehavior::Immediate)?;
+        let applied = tx
+            .prepare("SELECT version, checksum FROM schema_version ORDER BY version")?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<(i64, String)>, _>>()?;
+        let pending = match pending(MIGRATIONS, &applied, |m| m.sqlite) {
+            Ok(pending) => pending,
+            Err(e) => return Ok(Err(e)),
+        };
+        let mut versions = Vec::new();
+        for migration in pending {
+            tx.execute_batch(migration.sqlite)?;
+            tx.execute(
+                "INSERT INTO schema_version (version, name, checksum) VALUES (?1, ?2, ?3)",
+                (
+                    migration.version,
+                    migration.name,
+                    checksum(migration.sqlite),
+                ),
+            )?;
+            versions.push(migration.version);
+        }
+        tx.commit()?;
+        Ok(Ok(versions))
+    })
+    .await?
+}
+
+// PostgreSQL 마이그레이션 적용, 새로 적용된 버전 목록 반환
+pub async fn pg_apply(db: &PgPool) -> Result<Vec<i64>, MigrationError> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version BIGINT PRIMARY KEY,
+            name TEXT NOT NULL,
+            checksum TEXT NOT NULL,
+            applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
         )",
-        [],
-    )?;
-    db.execute("CREATE INDEX IF NOT EXISTS code ON email_auth (code)", [])?;
-    Ok(())
+    )
+    .execute(db)
+    .await?;
+
+    // 여러 인스턴스가 동시에 기동해도 한 곳에서만 적용되도록 잠금
+    let mut tx = db.begin().await?;
+    sqlx::query("SELECT pg_advisory_xact_lock(7205759403792793)")
+        .execute(&mut *tx)
+        .await?;
+    let applied: Vec<(i64, String)> =
+        sqlx::query_as("SELECT version, checksum FROM schema_version ORDER BY version")
+            .fetch_all(&mut *tx)
+            .await?;
+    let pending = pending(MIGRATIONS, &applied, |m| m.postgres)?;
+
+    let mut versions = Vec::new();
+    for migration in pending {
+        sqlx::raw_sql(migration.postgres).execute(&mut *tx).await?;
+        sqlx::query("INSERT INTO schema_version (version, name, checksum) VALUES ($1, $2, $3)")
+            .bind(migration.version)
+            .bind(migration.name)
+            .bind(checksum(migration.postgres))
+            .execute(&mut *tx)
+            .await?;
+        versions.push(migration.version);
+    }
+    tx.commit().await?;
+    Ok(versions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::pool::create_pool;
+
+    const TEST_MIGRATIONS: &[Migration] = &[
+        Migration {
+            version: 1,
+            name: "one",
+            sqlite: "CREATE TABLE one (id INTEGER);",
+            postgres: "",
+        },
+        Migration {
+            version: 2,
+            name: "two",
+            sqlite: "CREATE TABLE two (id INTEGER);",
+            postgres: "",
+        },
+        Migration {
+            version: 3,
+            name: "three",
+            sqlite: "CREATE TABLE three (id INTEGER);",
+            postgres: "",
+        },
+    ];
+
+    fn applied(versions: &[i64]) -> Vec<(i64, String)> {
+        versions
+            .iter()
+            .map(|&v| {
+                let migration = TEST_MIGRATIONS.iter().find(|m| m.version == v).unwrap();
+                (v, checksum(migration.sqlite))
+            })
+            .collect()
+    }
+
+    fn versions(pending: Vec<&Migration>) -> Vec<i64> {
+        pending.iter().map(|m| m.version).collect()
+    }
+
+    #[test]
+    fn migrations_are_numbered_in_order() {
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, i as i64 + 1, "{}", migration.name);
+        }
+    }
+
+    #[test]
+    fn pending_keeps_version_order() {
+        let result = pending(TEST_MIGRATIONS, &[], |m| m.sqlite).unwrap();
+        assert_eq!(versions(result), vec![1, 2, 3]);
+        // 이력이 순서대로 조회되지 않거나 중간 버전이 빠져 있어도 빠진 것만 순서대로
+        let result = pending(TEST_MIGRATIONS, &applied(&[3, 1]), |m| m.sqlite).unwrap();
+        assert_eq!(versions(result), vec![2]);
+        let result = pending(TEST_MIGRATIONS, &applied(&[1, 2, 3]), |m| m.sqlite).unwrap();
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn refuses_database_newer_than_binary() {
+        let mut history = applied(&[1, 2, 3]);
+        history.push((4, "unknown".to_string()));
+        assert!(matches!(
+            pending(TEST_MIGRATIONS, &history, |m| m.sqlite),
+            Err(MigrationError::DatabaseNewer {
+                database: 4,
+                binary: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn refuses_modified_migration() {
+        let mut history = applied(&[1, 2]);
+        history[1].1 = checksum("CREATE TABLE two (id TEXT);");
+        match pending(TEST_MIGRATIONS, &history, |m| m.sqlite) {
+            Err(MigrationError::ChecksumMismatch { version, name }) => {
+                assert_eq!((version, name.as_str()), (2, "two"));
+            }
+            _ => panic!("체크섬 불일치를 감지하지 못했습니다"),
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_sqlite_apply_runs_each_migration_once() {
+        let path = std::env::temp_dir().join(format!("migrate-{}.db", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let db = create_pool(&path).unwrap();
+        let (a, b) = tokio::join!(sqlite_apply(&db), sqlite_apply(&db));
+        let mut applied = a.unwrap();
+        applied.extend(b.unwrap());
+        applied.sort();
+        assert_eq!(applied, (1..=latest_version()).collect::<Vec<_>>());
+        assert!(sqlite_apply(&db).await.unwrap().is_empty());
+        drop(db);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path, suffix));
+        }
+    }
 }
@@ -1 +1,6 @@
+pub mod click_event;
 pub mod migrate;
+pub mod pool;
+pub mod postgres_repository;
+pub mod repository;
+pub mod sqlite_repository;
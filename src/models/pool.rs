@@ -0,0 +1,29 @@
+use crate::models::repository::DbError;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::Connection;
+
+pub type DbPool = r2d2::Pool<SqliteConnectionManager>;
+
+// SQLite 커넥션 풀 생성
+// WAL 모드로 읽기와 쓰기가 서로를 막지 않도록 하고, 쓰기 경합 시에는 잠시 대기
+pub fn create_pool(path: &str) -> Result<DbPool, r2d2::Error> {
+    let manager = SqliteConnectionManager::file(path).with_init(|conn| {
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA busy_timeout = 5000;")
+    });
+    r2d2::Pool::builder().build(manager)
+}
+
+// 블로킹 쿼리는 별도 스레드에서 실행해 런타임 워커를 점유하지 않도록 함
+pub async fn run<F, T>(pool: &DbPool, f: F) -> Result<T, DbError>
+where
+    F: FnOnce(&Connection) -> rusqlite::Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    let pool = pool.clone();
+    tokio::task::spawn_blocking(move || {
+        let conn = pool.get().map_err(DbError::Pool)?;
+        f(&conn).map_err(DbError::Sqlite)
+    })
+    .await
+    .map_err(DbError::Task)?
+}
@@ -0,0 +1,644 @@
+use crate::models::click_event::{ClickEvent, ClickInterval};
+use crate::models::repository::{
+    ApiKey, ApiKeyRepository, ClickBucket, ClickRepository, DbError, EmailAuthRepository, NewUrl,
+    Url, UrlRepository, UrlUpdate, WebhookDelivery, WebhookRepository,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::{PgPool, PgRow};
+use sqlx::Row;
+
+const URL_COLUMNS: &str = "id, random_key, email, ios_deep_link, ios_fallback_url, android_deep_link, android_fallback_url, default_fallback_url, webhook_url, head_html, redirect_status, is_verified, activates_at, expires_at, max_clicks, expired_action, expired_fallback_url, click_count, password_hash, alias, generated_key, android_package_name, og_title, og_description, og_image, twitter_card";
+
+pub struct PostgresRepository {
+    pool: PgPool,
+}
+
+impl PostgresRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+const DELIVERY_COLUMNS: &str =
+    "id, url_id, webhook_url, payload, status, attempts, next_attempt_at, last_error, created_at";
+
+fn delivery_from_row(row: &PgRow) -> Result<WebhookDelivery, sqlx::Error> {
+    Ok(WebhookDelivery {
+        id: row.try_get("id")?,
+        url_id: row.try_get("url_id")?,
+        webhook_url: row.try_get("webhook_url")?,
+        payload: row.try_get("payload")?,
+        status: row.try_get("status")?,
+        attempts: row.try_get("attempts")?,
+        next_attempt_at: row.try_get("next_attempt_at")?,
+        last_error: row.try_get("last_error")?,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+fn url_from_row(row: &PgRow) -> Result<Url, sqlx::Error> {
+    Ok(Url {
+        id: row.try_get("id")?,
+        random_key: row.try_get("random_key")?,
+        email: row.try_get("email")?,
+        ios_deep_link: row
+            .try_get::<Option<String>, _>("ios_deep_link")?
+            .unwrap_or_default(),
+        ios_fallback_url: row
+            .try_get::<Option<String>, _>("ios_fallback_url")?
+            .unwrap_or_default(),
+        android_deep_link: row
+            .try_get::<Option<String>, _>("android_deep_link")?
+            .unwrap_or_default(),
+        android_fallback_url: row
+            .try_get::<Option<String>, _>("android_fallback_url")?
+            .unwrap_or_default(),
+        default_fallback_url: row.try_get("default_fallback_url")?,
+        webhook_url: row
+            .try_get::<Option<String>, _>("webhook_url")?
+            .unwrap_or_default(),
+        head_html: row
+            .try_get::<Option<String>, _>("head_html")?
+            .unwrap_or_default(),
+        redirect_status: row.try_get("redirect_status")?,
+        is_verified: row.try_get("is_verified")?,
+        activates_at: row.try_get("activates_at")?,
+        expires_at: row.try_get("expires_at")?,
+        max_clicks: row.try_get("max_clicks")?,
+        expired_action: row.try_get("expired_action")?,
+        expired_fallback_url: row.try_get("expired_fallback_url")?,
+        click_count: row.try_get("click_count")?,
+        password_hash: row.try_get("password_hash")?,
+        alias: row.try_get("alias")?,
+        generated_key: row.try_get("generated_key")?,
+        android_package_name: row.try_get("android_package_name")?,
+        og_title: row.try_get("og_title")?,
+        og_description: row.try_get("og_description")?,
+        og_image: row.try_get("og_image")?,
+        twitter_card: row.try_get("twitter_card")?,
+    })
+}
+
+#[async_trait]
+impl UrlRepository for PostgresRepository {
+    async fn find(&self, id: i64) -> Result<Option<Url>, DbError> {
+        let row = sqlx::query(&format!(
+            "SELECT {URL_COLUMNS} FROM urls WHERE id = $1 AND is_deleted = FALSE"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(DbError::Postgres)?;
+        row.as_ref()
+            .map(url_from_row)
+            .transpose()
+            .map_err(DbError::Postgres)
+    }
+
+    async fn find_by_hashed_value(&self, hashed_value: &str) -> Result<Option<Url>, DbError> {
+        let row = sqlx::query(&format!(
+            "SELECT {URL_COLUMNS} FROM urls WHERE hashed_value = $1 AND is_deleted = FALSE ORDER BY id LIMIT 1"
+        ))
+        .bind(hashed_value)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(DbError::Postgres)?;
+        row.as_ref()
+            .map(url_from_row)
+            .transpose()
+            .map_err(DbError::Postgres)
+    }
+
+    async fn find_by_hashed_value_for_owner(
+        &self,
+        hashed_value: &str,
+        email: &str,
+    ) -> Result<Option<Url>, DbError> {
+        let row = sqlx::query(&format!(
+            "SELECT {URL_COLUMNS} FROM urls WHERE hashed_value = $1 AND email = $2 AND is_deleted = FALSE ORDER BY id LIMIT 1"
+        ))
+        .bind(hashed_value)
+        .bind(email)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(DbError::Postgres)?;
+        row.as_ref()
+            .map(url_from_row)
+            .transpose()
+            .map_err(DbError::Postgres)
+    }
+
+    async fn find_by_alias(&self, alias: &str) -> Result<Option<Url>, DbError> {
+        let row = sqlx::query(&format!(
+            "SELECT {URL_COLUMNS} FROM urls WHERE alias = $1 AND is_deleted = FALSE"
+        ))
+        .bind(alias)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(DbError::Postgres)?;
+        row.as_ref()
+            .map(url_from_row)
+            .transpose()
+            .map_err(DbError::Postgres)
+    }
+
+    async fn alias_exists(&self, alias: &str) -> Result<bool, DbError> {
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM urls WHERE alias = $1)")
+            .bind(alias)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(DbError::Postgres)
+    }
+
+    async fn find_by_generated_key(&self, key: &str) -> Result<Option<Url>, DbError> {
+        let row = sqlx::query(&format!(
+            "SELECT {URL_COLUMNS} FROM urls WHERE generated_key = $1 AND is_deleted = FALSE"
+        ))
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(DbError::Postgres)?;
+        row.as_ref()
+            .map(url_from_row)
+            .transpose()
+            .map_err(DbError::Postgres)
+    }
+
+    async fn generated_key_exists(&self, key: &str) -> Result<bool, DbError> {
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM urls WHERE generated_key = $1)")
+            .bind(key)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(DbError::Postgres)
+    }
+
+    async fn set_generated_key(&self, id: i64, key: &str) -> Result<(), DbError> {
+        sqlx::query("UPDATE urls SET generated_key = $1 WHERE id = $2")
+            .bind(key)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(DbError::Postgres)?;
+        Ok(())
+    }
+
+    async fn create(&self, url: NewUrl) -> Result<i64, DbError> {
+        sqlx::query_scalar(
+            "INSERT INTO urls (random_key, email, ios_deep_link, ios_fallback_url, android_deep_link, android_fallback_url, default_fallback_url, hashed_value, webhook_url, head_html, redirect_status, is_verified, webhook_secret, activates_at, expires_at, max_clicks, expired_action, expired_fallback_url, password_hash, alias, android_package_name, og_title, og_description, og_image, twitter_card) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25) RETURNING id",
+        )
+        .bind(&url.random_key)
+        .bind(&url.email)
+        .bind(&url.ios_deep_link)
+        .bind(&url.ios_fallback_url)
+        .bind(&url.android_deep_link)
+        .bind(&url.android_fallback_url)
+        .bind(&url.default_fallback_url)
+        .bind(&url.hashed_value)
+        .bind(&url.webhook_url)
+        .bind(&url.head_html)
+        .bind(url.redirect_status)
+        .bind(url.is_verified)
+        .bind(&url.webhook_secret)
+        .bind(url.activates_at)
+        .bind(url.expires_at)
+        .bind(url.max_clicks)
+        .bind(&url.expired_action)
+        .bind(&url.expired_fallback_url)
+        .bind(&url.password_hash)
+        .bind(&url.alias)
+        .bind(&url.android_package_name)
+        .bind(&url.og_title)
+        .bind(&url.og_description)
+        .bind(&url.og_image)
+        .bind(&url.twitter_card)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(DbError::Postgres)
+    }
+
+    async fn list(
+        &self,
+        email: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Url>, DbError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {URL_COLUMNS} FROM urls WHERE is_deleted = FALSE AND ($1::TEXT IS NULL OR email = $1) ORDER BY id DESC LIMIT $2 OFFSET $3"
+        ))
+        .bind(email)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Postgres)?;
+        rows.iter()
+            .map(url_from_row)
+            .collect::<Result<_, _>>()
+            .map_err(DbError::Postgres)
+    }
+
+    async fn count(&self, email: Option<&str>) -> Result<i64, DbError> {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM urls WHERE is_deleted = FALSE AND ($1::TEXT IS NULL OR email = $1)",
+        )
+        .bind(email)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(DbError::Postgres)
+    }
+
+    async fn update(&self, id: i64, update: UrlUpdate) -> Result<(), DbError> {
+        sqlx::query(
+            "UPDATE urls SET ios_deep_link = $1, ios_fallback_url = $2, android_deep_link = $3, android_fallback_url = $4, default_fallback_url = $5, hashed_value = $6, webhook_url = $7, head_html = $8, redirect_status = $9, activates_at = $10, expires_at = $11, max_clicks = $12, expired_action = $13, expired_fallback_url = $14, password_hash = $15, android_package_name = $16, og_title = $17, og_description = $18, og_image = $19, twitter_card = $20 WHERE id = $21 AND is_deleted = FALSE",
+        )
+        .bind(&update.ios_deep_link)
+        .bind(&update.ios_fallback_url)
+        .bind(&update.android_deep_link)
+        .bind(&update.android_fallback_url)
+        .bind(&update.default_fallback_url)
+        .bind(&update.hashed_value)
+        .bind(&update.webhook_url)
+        .bind(&update.head_html)
+        .bind(update.redirect_status)
+        .bind(update.activates_at)
+        .bind(update.expires_at)
+        .bind(update.max_clicks)
+        .bind(&update.expired_action)
+        .bind(&update.expired_fallback_url)
+        .bind(&update.password_hash)
+        .bind(&update.android_package_name)
+        .bind(&update.og_title)
+        .bind(&update.og_description)
+        .bind(&update.og_image)
+        .bind(&update.twitter_card)
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(DbError::Postgres)?;
+        Ok(())
+    }
+
+    async fn update_head_html(&self, id: i64, head_html: &str) -> Result<(), DbError> {
+        sqlx::query("UPDATE urls SET head_html = $1 WHERE id = $2")
+            .bind(head_html)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(DbError::Postgres)?;
+        Ok(())
+    }
+
+    async fn soft_delete(&self, id: i64) -> Result<bool, DbError> {
+        let result =
+            sqlx::query("UPDATE urls SET is_deleted = TRUE WHERE id = $1 AND is_deleted = FALSE")
+                .bind(id)
+                .execute(&self.pool)
+                .await
+                .map_err(DbError::Postgres)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn mark_verified(&self, id: i64, random_key: &str) -> Result<bool, DbError> {
+        let result =
+            sqlx::query("UPDATE urls SET is_verified = TRUE WHERE random_key = $1 AND id = $2")
+                .bind(random_key)
+                .bind(id)
+                .execute(&self.pool)
+                .await
+                .map_err(DbError::Postgres)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn consume_click(&self, id: i64) -> Result<bool, DbError> {
+        let result = sqlx::query(
+            "UPDATE urls SET click_count = click_count + 1 WHERE id = $1 AND (max_clicks IS NULL OR click_count < max_clicks)",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(DbError::Postgres)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn find_webhook_secret(&self, id: i64) -> Result<Option<String>, DbError> {
+        sqlx::query_scalar::<_, Option<String>>("SELECT webhook_secret FROM urls WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map(Option::flatten)
+            .map_err(DbError::Postgres)
+    }
+}
+
+#[async_trait]
+impl EmailAuthRepository for PostgresRepository {
+    async fn create(
+        &self,
+        short_key: &str,
+        code: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), DbError> {
+        sqlx::query("INSERT INTO email_auth (short_key, code, expires_at) VALUES ($1, $2, $3)")
+            .bind(short_key)
+            .bind(code)
+            .bind(expires_at)
+            .execute(&self.pool)
+            .await
+            .map_err(DbError::Postgres)?;
+        Ok(())
+    }
+
+    async fn find_short_key(&self, code: &str) -> Result<Option<String>, DbError> {
+        sqlx::query_scalar(
+            "SELECT short_key FROM email_auth WHERE code = $1 AND expires_at > NOW()",
+        )
+        .bind(code)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(DbError::Postgres)
+    }
+
+    async fn delete(&self, code: &str) -> Result<(), DbError> {
+        sqlx::query("DELETE FROM email_auth WHERE code = $1")
+            .bind(code)
+            .execute(&self.pool)
+            .await
+            .map_err(DbError::Postgres)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for PostgresRepository {
+    async fn create(&self, name: &str, owner_email: &str, key_hash: &str) -> Result<i64, DbError> {
+        sqlx::query_scalar(
+            "INSERT INTO api_keys (name, owner_email, key_hash) VALUES ($1, $2, $3) RETURNING id",
+        )
+        .bind(name)
+        .bind(owner_email)
+        .bind(key_hash)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(DbError::Postgres)
+    }
+
+    async fn find_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, DbError> {
+        let row = sqlx::query(
+            "SELECT owner_email FROM api_keys WHERE key_hash = $1 AND revoked_at IS NULL",
+        )
+        .bind(key_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(DbError::Postgres)?;
+        row.map(|row| {
+            Ok(ApiKey {
+                owner_email: row.try_get("owner_email")?,
+            })
+        })
+        .transpose()
+        .map_err(DbError::Postgres)
+    }
+}
+
+#[async_trait]
+impl WebhookRepository for PostgresRepository {
+    async fn enqueue(&self, url_id: i64, webhook_url: &str, payload: &str) -> Result<i64, DbError> {
+        sqlx::query_scalar(
+            "INSERT INTO webhook_deliveries (url_id, webhook_url, payload, next_attempt_at, created_at) VALUES ($1, $2, $3, NOW(), NOW()) RETURNING id",
+        )
+        .bind(url_id)
+        .bind(webhook_url)
+        .bind(payload)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(DbError::Postgres)
+    }
+
+    async fn claim_due(
+        &self,
+        now: DateTime<Utc>,
+        lease_until: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, DbError> {
+        // SKIP LOCKED 로 여러 인스턴스의 워커가 같은 건을 동시에 가져가지 않도록 함
+        let rows = sqlx::query(&format!(
+            "UPDATE webhook_deliveries SET next_attempt_at = $2 WHERE id IN (SELECT id FROM webhook_deliveries WHERE status = 'pending' AND next_attempt_at <= $1 ORDER BY next_attempt_at LIMIT $3 FOR UPDATE SKIP LOCKED) RETURNING {DELIVERY_COLUMNS}"
+        ))
+        .bind(now)
+        .bind(lease_until)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Postgres)?;
+        rows.iter()
+            .map(delivery_from_row)
+            .collect::<Result<_, _>>()
+            .map_err(DbError::Postgres)
+    }
+
+    async fn mark_delivered(&self, id: i64, attempts: i32) -> Result<(), DbError> {
+        sqlx::query(
+            "UPDATE webhook_deliveries SET status = 'delivered', attempts = $2, delivered_at = NOW(), last_error = NULL WHERE id = $1",
+        )
+        .bind(id)
+        .bind(attempts)
+        .execute(&self.pool)
+        .await
+        .map_err(DbError::Postgres)?;
+        Ok(())
+    }
+
+    async fn mark_retry(
+        &self,
+        id: i64,
+        attempts: i32,
+        next_attempt_at: DateTime<Utc>,
+        error: &str,
+    ) -> Result<(), DbError> {
+        sqlx::query(
+            "UPDATE webhook_deliveries SET attempts = $2, next_attempt_at = $3, last_error = $4 WHERE id = $1",
+        )
+        .bind(id)
+        .bind(attempts)
+        .bind(next_attempt_at)
+        .bind(error)
+        .execute(&self.pool)
+        .await
+        .map_err(DbError::Postgres)?;
+        Ok(())
+    }
+
+    async fn mark_failed(&self, id: i64, attempts: i32, error: &str) -> Result<(), DbError> {
+        sqlx::query(
+            "UPDATE webhook_deliveries SET status = 'failed', attempts = $2, last_error = $3 WHERE id = $1",
+        )
+        .bind(id)
+        .bind(attempts)
+        .bind(error)
+        .execute(&self.pool)
+        .await
+        .map_err(DbError::Postgres)?;
+        Ok(())
+    }
+
+    async fn list_failed(
+        &self,
+        url_id: i64,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<WebhookDelivery>, DbError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries WHERE url_id = $1 AND status = 'failed' ORDER BY id DESC LIMIT $2 OFFSET $3"
+        ))
+        .bind(url_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Postgres)?;
+        rows.iter()
+            .map(delivery_from_row)
+            .collect::<Result<_, _>>()
+            .map_err(DbError::Postgres)
+    }
+
+    async fn count_failed(&self, url_id: i64) -> Result<i64, DbError> {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM webhook_deliveries WHERE url_id = $1 AND status = 'failed'",
+        )
+        .bind(url_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(DbError::Postgres)
+    }
+}
+
+#[async_trait]
+impl ClickRepository for PostgresRepository {
+    async fn record(&self, event: ClickEvent) -> Result<(), DbError> {
+        let visitor_hash = event.visitor_hash.clone();
+        let mut tx = self.pool.begin().await.map_err(DbError::Postgres)?;
+        for interval in [ClickInterval::Hour, ClickInterval::Day] {
+            let bucket_start = interval.truncate(event.occurred_at);
+            // 같은 구간에 이미 접속한 방문자면 순 방문자 수는 그대로 둠
+            let seen: bool = sqlx::query_scalar(
+                "SELECT EXISTS (SELECT 1 FROM clicks WHERE url_id = $1 AND visitor_hash = $2 AND occurred_at >= $3 AND occurred_at < $4)",
+            )
+            .bind(event.url_id)
+            .bind(&visitor_hash)
+            .bind(bucket_start)
+            .bind(bucket_start + interval.step())
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(DbError::Postgres)?;
+            let table = interval.rollup_table();
+            sqlx::query(&format!(
+                "INSERT INTO {table} (url_id, bucket_start, clicks, unique_clicks) VALUES ($1, $2, 1, $3) ON CONFLICT (url_id, bucket_start) DO UPDATE SET clicks = {table}.clicks + 1, unique_clicks = {table}.unique_clicks + excluded.unique_clicks"
+            ))
+            .bind(event.url_id)
+            .bind(bucket_start)
+            .bind(i64::from(!seen))
+            .execute(&mut *tx)
+            .await
+            .map_err(DbError::Postgres)?;
+        }
+        sqlx::query(
+            "INSERT INTO clicks (url_id, occurred_at, platform, visitor_hash, referer, accept_language, query, destination) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(event.url_id)
+        .bind(event.occurred_at)
+        .bind(event.platform.as_str())
+        .bind(&visitor_hash)
+        .bind(&event.referer)
+        .bind(&event.accept_language)
+        .bind(&event.query)
+        .bind(&event.destination)
+        .execute(&mut *tx)
+        .await
+        .map_err(DbError::Postgres)?;
+        tx.commit().await.map_err(DbError::Postgres)
+    }
+
+    async fn count(
+        &self,
+        url_id: i64,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<(i64, i64), DbError> {
+        sqlx::query_as(
+            "SELECT COUNT(*), COUNT(DISTINCT visitor_hash) FROM clicks WHERE url_id = $1 AND occurred_at >= $2 AND occurred_at < $3",
+        )
+        .bind(url_id)
+        .bind(from)
+        .bind(to)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(DbError::Postgres)
+    }
+
+    async fn count_by_platform(
+        &self,
+        url_id: i64,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<(String, i64)>, DbError> {
+        sqlx::query_as(
+            "SELECT platform, COUNT(*) FROM clicks WHERE url_id = $1 AND occurred_at >= $2 AND occurred_at < $3 GROUP BY platform",
+        )
+        .bind(url_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Postgres)
+    }
+
+    async fn top_referers(
+        &self,
+        url_id: i64,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<(String, i64)>, DbError> {
+        sqlx::query_as(
+            "SELECT referer, COUNT(*) AS hits FROM clicks WHERE url_id = $1 AND occurred_at >= $2 AND occurred_at < $3 AND referer IS NOT NULL GROUP BY referer ORDER BY hits DESC, referer LIMIT $4",
+        )
+        .bind(url_id)
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Postgres)
+    }
+
+    async fn time_series(
+        &self,
+        url_id: i64,
+        interval: ClickInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<ClickBucket>, DbError> {
+        let rows = sqlx::query(&format!(
+            "SELECT bucket_start, clicks, unique_clicks FROM {} WHERE url_id = $1 AND bucket_start >= $2 AND bucket_start < $3 ORDER BY bucket_start",
+            interval.rollup_table()
+        ))
+        .bind(url_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DbError::Postgres)?;
+        rows.iter()
+            .map(|row| {
+                Ok(ClickBucket {
+                    bucket_start: row.try_get("bucket_start")?,
+                    clicks: row.try_get("clicks")?,
+                    unique_clicks: row.try_get("unique_clicks")?,
+                })
+            })
+            .collect::<Result<_, sqlx::Error>>()
+            .map_err(DbError::Postgres)
+    }
+}
@@ -0,0 +1,399 @@
+use crate::models::click_event::{ClickEvent, ClickInterval};
+use crate::models::migrate::{pg_apply, sqlite_apply, MigrationError};
+use crate::models::pool::{create_pool, DbPool};
+use crate::models::postgres_repository::PostgresRepository;
+use crate::models::sqlite_repository::SqliteRepository;
+use crate::utils::converter::make_short_key;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use std::fmt;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub enum DbError {
+    Pool(r2d2::Error),
+    Sqlite(rusqlite::Error),
+    Postgres(sqlx::Error),
+    Task(tokio::task::JoinError),
+}
+
+impl fmt::Display for DbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DbError::Pool(e) => write!(f, "커넥션 풀 오류: {}", e),
+            DbError::Sqlite(e) => write!(f, "SQLite 쿼리 오류: {}", e),
+            DbError::Postgres(e) => write!(f, "PostgreSQL 쿼리 오류: {}", e),
+            DbError::Task(e) => write!(f, "작업 실행 오류: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DbError {}
+
+impl DbError {
+    // 유니크 인덱스 위반 (동시에 같은 별칭으로 생성하는 경우 등)
+    pub fn is_unique_violation(&self) -> bool {
+        match self {
+            DbError::Sqlite(rusqlite::Error::SqliteFailure(e, _)) => {
+                e.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_UNIQUE
+            }
+            DbError::Postgres(sqlx::Error::Database(e)) => e.is_unique_violation(),
+            _ => false,
+        }
+    }
+}
+
+// urls 테이블 레코드
+#[derive(Clone, Debug)]
+pub struct Url {
+    pub id: i64,
+    pub random_key: String,
+    pub email: String,
+    pub ios_deep_link: String,
+    pub ios_fallback_url: String,
+    pub android_deep_link: String,
+    pub android_fallback_url: String,
+    // intent:// URL 에 넣을 Android 앱 패키지 이름 (없으면 빈 문자열)
+    pub android_package_name: String,
+    pub default_fallback_url: String,
+    pub webhook_url: String,
+    pub head_html: String,
+    // 미리보기용 메타데이터 (head_html 과 따로 관리, 빈 문자열이면 지정하지 않음)
+    pub og_title: String,
+    pub og_description: String,
+    pub og_image: String,
+    pub twitter_card: String,
+    // 딥링크 시도가 필요 없을 때 사용하는 리다이렉트 상태 코드 (301, 302, 307, 308)
+    pub redirect_status: i32,
+    pub is_verified: bool,
+    // 이 시각 이전에는 접속 불가
+    pub activates_at: Option<DateTime<Utc>>,
+    // 이 시각부터 만료
+    pub expires_at: Option<DateTime<Utc>>,
+    // 최대 접속 횟수, 넘으면 만료
+    pub max_clicks: Option<i32>,
+    // 만료 시 동작 (gone: 410, page: 만료 안내 페이지, redirect: expired_fallback_url 로 이동)
+    pub expired_action: String,
+    pub expired_fallback_url: String,
+    pub click_count: i32,
+    // 비밀번호 보호 링크의 Argon2 해시
+    pub password_hash: Option<String>,
+    // 사용자가 지정한 단축 키 (지정하면 생성된 키 대신 사용)
+    pub alias: Option<String>,
+    // 생성 당시 배포 설정의 인코딩 방식으로 발급한 키 (이전 버전에서 생성되었으면 None)
+    pub generated_key: Option<String>,
+}
+
+impl Url {
+    pub fn short_key(&self) -> String {
+        self.alias
+            .clone()
+            .or_else(|| self.generated_key.clone())
+            .unwrap_or_else(|| make_short_key(self.id, &self.random_key))
+    }
+
+    // 이 URL 로 해석되는 모든 단축 키 (캐시 제거용)
+    pub fn short_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .alias
+            .iter()
+            .chain(self.generated_key.iter())
+            .cloned()
+            .collect();
+        keys.push(make_short_key(self.id, &self.random_key));
+        keys
+    }
+}
+
+// 새로 저장할 URL
+#[derive(Clone, Debug)]
+pub struct NewUrl {
+    pub random_key: String,
+    pub email: String,
+    pub ios_deep_link: String,
+    pub ios_fallback_url: String,
+    pub android_deep_link: String,
+    pub android_fallback_url: String,
+    // intent:// URL 에 넣을 Android 앱 패키지 이름 (없으면 빈 문자열)
+    pub android_package_name: String,
+    pub default_fallback_url: String,
+    pub hashed_value: String,
+    pub webhook_url: String,
+    pub head_html: String,
+    pub og_title: String,
+    pub og_description: String,
+    pub og_image: String,
+    pub twitter_card: String,
+    pub redirect_status: i32,
+    // API 키로 생성한 URL 은 이메일 인증 없이 바로 활성화
+    pub is_verified: bool,
+    pub activates_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub max_clicks: Option<i32>,
+    pub expired_action: String,
+    pub expired_fallback_url: String,
+    pub password_hash: Option<String>,
+    pub alias: Option<String>,
+    // 웹훅 서명 키 (생성 응답에서 한 번만 노출)
+    pub webhook_secret: String,
+}
+
+// 수정 가능한 URL 필드 (hashed_value 는 변경된 값으로 다시 계산해 전달)
+#[derive(Clone, Debug)]
+pub struct UrlUpdate {
+    pub ios_deep_link: String,
+    pub ios_fallback_url: String,
+    pub android_deep_link: String,
+    pub android_fallback_url: String,
+    // intent:// URL 에 넣을 Android 앱 패키지 이름 (없으면 빈 문자열)
+    pub android_package_name: String,
+    pub default_fallback_url: String,
+    pub hashed_value: String,
+    pub webhook_url: String,
+    pub head_html: String,
+    pub og_title: String,
+    pub og_description: String,
+    pub og_image: String,
+    pub twitter_card: String,
+    pub redirect_status: i32,
+    pub activates_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub max_clicks: Option<i32>,
+    pub expired_action: String,
+    pub expired_fallback_url: String,
+    pub password_hash: Option<String>,
+}
+
+#[async_trait]
+pub trait UrlRepository: Send + Sync {
+    // 삭제되지 않은 URL (인증 여부 무관)
+    async fn find(&self, id: i64) -> Result<Option<Url>, DbError>;
+    // 삭제되지 않은 URL 중 hashed_value 가 일치하는 URL (여럿이면 가장 먼저 생성된 URL)
+    async fn find_by_hashed_value(&self, hashed_value: &str) -> Result<Option<Url>, DbError>;
+    // 위와 같지만 소유자 이메일이 일치하는 URL 만 (API 키 요청의 중복 확인용)
+    async fn find_by_hashed_value_for_owner(
+        &self,
+        hashed_value: &str,
+        email: &str,
+    ) -> Result<Option<Url>, DbError>;
+    // 삭제되지 않은 URL 중 별칭이 일치하는 URL
+    async fn find_by_alias(&self, alias: &str) -> Result<Option<Url>, DbError>;
+    // 삭제된 URL 을 포함해 별칭이 사용된 적 있는지 (삭제된 URL 의 별칭은 재사용하지 않음)
+    async fn alias_exists(&self, alias: &str) -> Result<bool, DbError>;
+    // 삭제되지 않은 URL 중 발급한 키가 일치하는 URL
+    async fn find_by_generated_key(&self, key: &str) -> Result<Option<Url>, DbError>;
+    // 삭제된 URL 을 포함해 발급한 키가 사용된 적 있는지
+    async fn generated_key_exists(&self, key: &str) -> Result<bool, DbError>;
+    async fn set_generated_key(&self, id: i64, key: &str) -> Result<(), DbError>;
+    async fn create(&self, url: NewUrl) -> Result<i64, DbError>;
+    // 삭제되지 않은 URL 을 최신순으로 조회, email 이 있으면 해당 이메일로 필터
+    async fn list(&self, email: Option<&str>, limit: i64, offset: i64)
+        -> Result<Vec<Url>, DbError>;
+    async fn count(&self, email: Option<&str>) -> Result<i64, DbError>;
+    async fn update(&self, id: i64, update: UrlUpdate) -> Result<(), DbError>;
+    async fn update_head_html(&self, id: i64, head_html: &str) -> Result<(), DbError>;
+    // 소프트 삭제, 변경 여부 반환
+    async fn soft_delete(&self, id: i64) -> Result<bool, DbError>;
+    // id 와 random_key 가 모두 일치할 때만 인증 처리, 변경 여부 반환
+    async fn mark_verified(&self, id: i64, random_key: &str) -> Result<bool, DbError>;
+    // 최대 접속 횟수 안에서 접속 수를 1 늘림, 이미 한도에 도달했으면 false
+    async fn consume_click(&self, id: i64) -> Result<bool, DbError>;
+    // 웹훅 서명 키 (이전 버전에서 생성되어 키가 없으면 None)
+    async fn find_webhook_secret(&self, id: i64) -> Result<Option<String>, DbError>;
+}
+
+#[async_trait]
+pub trait EmailAuthRepository: Send + Sync {
+    async fn create(
+        &self,
+        short_key: &str,
+        code: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), DbError>;
+    // 만료되지 않은 코드에 연결된 short_key
+    async fn find_short_key(&self, code: &str) -> Result<Option<String>, DbError>;
+    async fn delete(&self, code: &str) -> Result<(), DbError>;
+}
+
+// api_keys 테이블 레코드 (키 원문은 저장하지 않고 해시만 보관)
+#[derive(Clone, Debug)]
+pub struct ApiKey {
+    pub owner_email: String,
+}
+
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync {
+    async fn create(&self, name: &str, owner_email: &str, key_hash: &str) -> Result<i64, DbError>;
+    // 폐기되지 않은 키 중 해시가 일치하는 키
+    async fn find_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, DbError>;
+}
+
+// webhook_deliveries 테이블 레코드 (웹훅 전송 대기열)
+#[derive(Clone, Debug)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub url_id: i64,
+    pub webhook_url: String,
+    pub payload: String,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait WebhookRepository: Send + Sync {
+    async fn enqueue(&self, url_id: i64, webhook_url: &str, payload: &str) -> Result<i64, DbError>;
+    // 전송 시각이 된 대기 건을 가져오면서 lease_until 까지 다른 워커가 가져가지 못하도록 표시
+    async fn claim_due(
+        &self,
+        now: DateTime<Utc>,
+        lease_until: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, DbError>;
+    async fn mark_delivered(&self, id: i64, attempts: i32) -> Result<(), DbError>;
+    async fn mark_retry(
+        &self,
+        id: i64,
+        attempts: i32,
+        next_attempt_at: DateTime<Utc>,
+        error: &str,
+    ) -> Result<(), DbError>;
+    async fn mark_failed(&self, id: i64, attempts: i32, error: &str) -> Result<(), DbError>;
+    // 재시도 한도를 넘겨 최종 실패한 전송 건
+    async fn list_failed(
+        &self,
+        url_id: i64,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<WebhookDelivery>, DbError>;
+    async fn count_failed(&self, url_id: i64) -> Result<i64, DbError>;
+}
+
+// 구간별 접속 수 (click_rollups_hourly / click_rollups_daily)
+#[derive(Clone, Debug)]
+pub struct ClickBucket {
+    pub bucket_start: DateTime<Utc>,
+    pub clicks: i64,
+    pub unique_clicks: i64,
+}
+
+#[async_trait]
+pub trait ClickRepository: Send + Sync {
+    // 접속 기록 저장과 시간/일 단위 집계 갱신을 한 트랜잭션으로 처리
+    async fn record(&self, event: ClickEvent) -> Result<(), DbError>;
+    // [from, to) 구간의 전체 접속 수와 순 방문자 수
+    async fn count(
+        &self,
+        url_id: i64,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<(i64, i64), DbError>;
+    async fn count_by_platform(
+        &self,
+        url_id: i64,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<(String, i64)>, DbError>;
+    async fn top_referers(
+        &self,
+        url_id: i64,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<(String, i64)>, DbError>;
+    // 집계 테이블에서 조회, 접속이 없던 구간은 포함되지 않음
+    async fn time_series(
+        &self,
+        url_id: i64,
+        interval: ClickInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<ClickBucket>, DbError>;
+}
+
+pub struct Repositories {
+    pub urls: Arc<dyn UrlRepository>,
+    pub email_auth: Arc<dyn EmailAuthRepository>,
+    pub api_keys: Arc<dyn ApiKeyRepository>,
+    pub webhooks: Arc<dyn WebhookRepository>,
+    pub clicks: Arc<dyn ClickRepository>,
+}
+
+// DATABASE_URL 에 따라 선택된 저장소 백엔드
+pub enum Database {
+    Sqlite(DbPool),
+    Postgres(PgPool),
+}
+
+impl Database {
+    // postgres:// 또는 postgresql:// 이면 PostgreSQL, 그 외에는 SQLite 파일 경로로 취급
+    pub async fn open(database_url: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        if database_url.starts_with("postgres://") || database_url.starts_with("postgresql://") {
+            let pool = PgPoolOptions::new().connect(database_url).await?;
+            Ok(Database::Postgres(pool))
+        } else {
+            let path = database_url.trim_start_matches("sqlite://");
+            Ok(Database::Sqlite(create_pool(path)?))
+        }
+    }
+
+    // 아직 적용되지 않은 마이그레이션 적용
+    pub async fn migrate(&self) -> Result<Vec<i64>, MigrationError> {
+        match self {
+            Database::Sqlite(pool) => sqlite_apply(pool).await,
+            Database::Postgres(pool) => pg_apply(pool).await,
+        }
+    }
+
+    pub fn repositories(&self) -> Repositories {
+        match self {
+            Database::Sqlite(pool) => {
+                let repository = Arc::new(SqliteRepository::new(pool.clone()));
+                Repositories {
+                    urls: repository.clone(),
+                    email_auth: repository.clone(),
+                    api_keys: repository.clone(),
+                    webhooks: repository.clone(),
+                    clicks: repository,
+                }
+            }
+            Database::Postgres(pool) => {
+                let repository = Arc::new(PostgresRepository::new(pool.clone()));
+                Repositories {
+                    urls: repository.clone(),
+                    email_auth: repository.clone(),
+                    api_keys: repository.clone(),
+                    webhooks: repository.clone(),
+                    clicks: repository,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_sqlite_unique_violation() {
+        let db = rusqlite::Connection::open_in_memory().unwrap();
+        db.execute_batch(
+            "CREATE TABLE t (alias TEXT NOT NULL); CREATE UNIQUE INDEX idx_t_alias ON t (alias);",
+        )
+        .unwrap();
+        db.execute("INSERT INTO t (alias) VALUES ('a')", [])
+            .unwrap();
+        let duplicate = db
+            .execute("INSERT INTO t (alias) VALUES ('a')", [])
+            .unwrap_err();
+        assert!(DbError::Sqlite(duplicate).is_unique_violation());
+        let not_null = db
+            .execute("INSERT INTO t (alias) VALUES (NULL)", [])
+            .unwrap_err();
+        assert!(!DbError::Sqlite(not_null).is_unique_violation());
+    }
+}
@@ -0,0 +1,577 @@
+use crate::models::click_event::{ClickEvent, ClickInterval};
+use crate::models::pool::{self, DbPool};
+use crate::models::repository::{
+    ApiKey, ApiKeyRepository, ClickBucket, ClickRepository, DbError, EmailAuthRepository, NewUrl,
+    Url, UrlRepository, UrlUpdate, WebhookDelivery, WebhookRepository,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, OptionalExtension, Row};
+
+const URL_COLUMNS: &str = "id, random_key, email, ios_deep_link, ios_fallback_url, android_deep_link, android_fallback_url, default_fallback_url, webhook_url, head_html, redirect_status, is_verified, activates_at, expires_at, max_clicks, expired_action, expired_fallback_url, click_count, password_hash, alias, generated_key, android_package_name, og_title, og_description, og_image, twitter_card";
+
+pub struct SqliteRepository {
+    pool: DbPool,
+}
+
+impl SqliteRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+const DELIVERY_COLUMNS: &str =
+    "id, url_id, webhook_url, payload, status, attempts, next_attempt_at, last_error, created_at";
+
+fn delivery_from_row(row: &Row) -> rusqlite::Result<WebhookDelivery> {
+    Ok(WebhookDelivery {
+        id: row.get(0)?,
+        url_id: row.get(1)?,
+        webhook_url: row.get(2)?,
+        payload: row.get(3)?,
+        status: row.get(4)?,
+        attempts: row.get(5)?,
+        next_attempt_at: row.get(6)?,
+        last_error: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+fn url_from_row(row: &Row) -> rusqlite::Result<Url> {
+    Ok(Url {
+        id: row.get(0)?,
+        random_key: row.get(1)?,
+        email: row.get(2)?,
+        ios_deep_link: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+        ios_fallback_url: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+        android_deep_link: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+        android_fallback_url: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
+        default_fallback_url: row.get(7)?,
+        webhook_url: row.get::<_, Option<String>>(8)?.unwrap_or_default(),
+        head_html: row.get::<_, Option<String>>(9)?.unwrap_or_default(),
+        redirect_status: row.get(10)?,
+        is_verified: row.get(11)?,
+        activates_at: row.get(12)?,
+        expires_at: row.get(13)?,
+        max_clicks: row.get(14)?,
+        expired_action: row.get(15)?,
+        expired_fallback_url: row.get(16)?,
+        click_count: row.get(17)?,
+        password_hash: row.get(18)?,
+        alias: row.get(19)?,
+        generated_key: row.get(20)?,
+        android_package_name: row.get(21)?,
+        og_title: row.get(22)?,
+        og_description: row.get(23)?,
+        og_image: row.get(24)?,
+        twitter_card: row.get(25)?,
+    })
+}
+
+#[async_trait]
+impl UrlRepository for SqliteRepository {
+    async fn find(&self, id: i64) -> Result<Option<Url>, DbError> {
+        pool::run(&self.pool, move |db| {
+            db.query_row(
+                &format!("SELECT {URL_COLUMNS} FROM urls WHERE id = ?1 AND is_deleted = 0"),
+                [id],
+                url_from_row,
+            )
+            .optional()
+        })
+        .await
+    }
+
+    async fn find_by_hashed_value(&self, hashed_value: &str) -> Result<Option<Url>, DbError> {
+        let hashed_value = hashed_value.to_string();
+        pool::run(&self.pool, move |db| {
+            db.query_row(
+                &format!(
+                    "SELECT {URL_COLUMNS} FROM urls WHERE hashed_value = ?1 AND is_deleted = 0 ORDER BY id LIMIT 1"
+                ),
+                [&hashed_value],
+                url_from_row,
+            )
+            .optional()
+        })
+        .await
+    }
+
+    async fn find_by_hashed_value_for_owner(
+        &self,
+        hashed_value: &str,
+        email: &str,
+    ) -> Result<Option<Url>, DbError> {
+        let hashed_value = hashed_value.to_string();
+        let email = email.to_string();
+        pool::run(&self.pool, move |db| {
+            db.query_row(
+                &format!(
+                    "SELECT {URL_COLUMNS} FROM urls WHERE hashed_value = ?1 AND email = ?2 AND is_deleted = 0 ORDER BY id LIMIT 1"
+                ),
+                params![hashed_value, email],
+                url_from_row,
+            )
+            .optional()
+        })
+        .await
+    }
+
+    async fn find_by_alias(&self, alias: &str) -> Result<Option<Url>, DbError> {
+        let alias = alias.to_string();
+        pool::run(&self.pool, move |db| {
+            db.query_row(
+                &format!("SELECT {URL_COLUMNS} FROM urls WHERE alias = ?1 AND is_deleted = 0"),
+                [&alias],
+                url_from_row,
+            )
+            .optional()
+        })
+        .await
+    }
+
+    async fn alias_exists(&self, alias: &str) -> Result<bool, DbError> {
+        let alias = alias.to_string();
+        pool::run(&self.pool, move |db| {
+            db.query_row(
+                "SELECT EXISTS (SELECT 1 FROM urls WHERE alias = ?1)",
+                [&alias],
+                |row| row.get(0),
+            )
+        })
+        .await
+    }
+
+    async fn find_by_generated_key(&self, key: &str) -> Result<Option<Url>, DbError> {
+        let key = key.to_string();
+        pool::run(&self.pool, move |db| {
+            db.query_row(
+                &format!(
+                    "SELECT {URL_COLUMNS} FROM urls WHERE generated_key = ?1 AND is_deleted = 0"
+                ),
+                [&key],
+                url_from_row,
+            )
+            .optional()
+        })
+        .await
+    }
+
+    async fn generated_key_exists(&self, key: &str) -> Result<bool, DbError> {
+        let key = key.to_string();
+        pool::run(&self.pool, move |db| {
+            db.query_row(
+                "SELECT EXISTS (SELECT 1 FROM urls WHERE generated_key = ?1)",
+                [&key],
+                |row| row.get(0),
+            )
+        })
+        .await
+    }
+
+    async fn set_generated_key(&self, id: i64, key: &str) -> Result<(), DbError> {
+        let key = key.to_string();
+        pool::run(&self.pool, move |db| {
+            db.execute(
+                "UPDATE urls SET generated_key = ?1 WHERE id = ?2",
+                (&key, id),
+            )
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn create(&self, url: NewUrl) -> Result<i64, DbError> {
+        pool::run(&self.pool, move |db| {
+            db.query_row(
+                "INSERT INTO urls (random_key, email, ios_deep_link, ios_fallback_url, android_deep_link, android_fallback_url, default_fallback_url, hashed_value, webhook_url, head_html, redirect_status, is_verified, webhook_secret, activates_at, expires_at, max_clicks, expired_action, expired_fallback_url, password_hash, alias, android_package_name, og_title, og_description, og_image, twitter_card) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25) RETURNING id",
+                params![&url.random_key, &url.email, &url.ios_deep_link, &url.ios_fallback_url, &url.android_deep_link, &url.android_fallback_url, &url.default_fallback_url, &url.hashed_value, &url.webhook_url, &url.head_html, url.redirect_status, url.is_verified, &url.webhook_secret, url.activates_at, url.expires_at, url.max_clicks, &url.expired_action, &url.expired_fallback_url, &url.password_hash, &url.alias, &url.android_package_name, &url.og_title, &url.og_description, &url.og_image, &url.twitter_card],
+                |row| row.get::<_, i64>(0),
+            )
+        })
+        .await
+    }
+
+    async fn list(
+        &self,
+        email: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Url>, DbError> {
+        let email = email.map(str::to_string);
+        pool::run(&self.pool, move |db| {
+            db.prepare(&format!(
+                "SELECT {URL_COLUMNS} FROM urls WHERE is_deleted = 0 AND (?1 IS NULL OR email = ?1) ORDER BY id DESC LIMIT ?2 OFFSET ?3"
+            ))?
+            .query_map((&email, limit, offset), url_from_row)?
+            .collect()
+        })
+        .await
+    }
+
+    async fn count(&self, email: Option<&str>) -> Result<i64, DbError> {
+        let email = email.map(str::to_string);
+        pool::run(&self.pool, move |db| {
+            db.query_row(
+                "SELECT COUNT(*) FROM urls WHERE is_deleted = 0 AND (?1 IS NULL OR email = ?1)",
+                [&email],
+                |row| row.get(0),
+            )
+        })
+        .await
+    }
+
+    async fn update(&self, id: i64, update: UrlUpdate) -> Result<(), DbError> {
+        pool::run(&self.pool, move |db| {
+            db.execute(
+                "UPDATE urls SET ios_deep_link = ?1, ios_fallback_url = ?2, android_deep_link = ?3, android_fallback_url = ?4, default_fallback_url = ?5, hashed_value = ?6, webhook_url = ?7, head_html = ?8, redirect_status = ?9, activates_at = ?10, expires_at = ?11, max_clicks = ?12, expired_action = ?13, expired_fallback_url = ?14, password_hash = ?15, android_package_name = ?16, og_title = ?17, og_description = ?18, og_image = ?19, twitter_card = ?20 WHERE id = ?21 AND is_deleted = 0",
+                params![&update.ios_deep_link, &update.ios_fallback_url, &update.android_deep_link, &update.android_fallback_url, &update.default_fallback_url, &update.hashed_value, &update.webhook_url, &update.head_html, update.redirect_status, update.activates_at, update.expires_at, update.max_clicks, &update.expired_action, &update.expired_fallback_url, &update.password_hash, &update.android_package_name, &update.og_title, &update.og_description, &update.og_image, &update.twitter_card, id],
+            )
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn update_head_html(&self, id: i64, head_html: &str) -> Result<(), DbError> {
+        let head_html = head_html.to_string();
+        pool::run(&self.pool, move |db| {
+            db.execute(
+                "UPDATE urls SET head_html = ?1 WHERE id = ?2",
+                (&head_html, id),
+            )
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn soft_delete(&self, id: i64) -> Result<bool, DbError> {
+        let deleted = pool::run(&self.pool, move |db| {
+            db.execute(
+                "UPDATE urls SET is_deleted = 1 WHERE id = ?1 AND is_deleted = 0",
+                [id],
+            )
+        })
+        .await?;
+        Ok(deleted > 0)
+    }
+
+    async fn mark_verified(&self, id: i64, random_key: &str) -> Result<bool, DbError> {
+        let random_key = random_key.to_string();
+        let updated = pool::run(&self.pool, move |db| {
+            db.execute(
+                "UPDATE urls SET is_verified = 1 WHERE random_key = ?1 AND id = ?2",
+                (&random_key, id),
+            )
+        })
+        .await?;
+        Ok(updated > 0)
+    }
+
+    async fn consume_click(&self, id: i64) -> Result<bool, DbError> {
+        let updated = pool::run(&self.pool, move |db| {
+            db.execute(
+                "UPDATE urls SET click_count = click_count + 1 WHERE id = ?1 AND (max_clicks IS NULL OR click_count < max_clicks)",
+                [id],
+            )
+        })
+        .await?;
+        Ok(updated > 0)
+    }
+
+    async fn find_webhook_secret(&self, id: i64) -> Result<Option<String>, DbError> {
+        pool::run(&self.pool, move |db| {
+            db.query_row(
+                "SELECT webhook_secret FROM urls WHERE id = ?1",
+                [id],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .optional()
+            .map(Option::flatten)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl EmailAuthRepository for SqliteRepository {
+    async fn create(
+        &self,
+        short_key: &str,
+        code: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), DbError> {
+        let (short_key, code) = (short_key.to_string(), code.to_string());
+        pool::run(&self.pool, move |db| {
+            db.execute(
+                "INSERT INTO email_auth (short_key, code, expires_at) VALUES (?1, ?2, ?3)",
+                (&short_key, &code, expires_at.naive_utc().to_string()),
+            )
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn find_short_key(&self, code: &str) -> Result<Option<String>, DbError> {
+        let code = code.to_string();
+        pool::run(&self.pool, move |db| {
+            db.query_row(
+                "SELECT short_key FROM email_auth WHERE code = ?1 AND expires_at > datetime('now')",
+                [&code],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+        })
+        .await
+    }
+
+    async fn delete(&self, code: &str) -> Result<(), DbError> {
+        let code = code.to_string();
+        pool::run(&self.pool, move |db| {
+            db.execute("DELETE FROM email_auth WHERE code = ?1", [&code])
+        })
+        .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for SqliteRepository {
+    async fn create(&self, name: &str, owner_email: &str, key_hash: &str) -> Result<i64, DbError> {
+        let (name, owner_email, key_hash) = (
+            name.to_string(),
+            owner_email.to_string(),
+            key_hash.to_string(),
+        );
+        pool::run(&self.pool, move |db| {
+            db.query_row(
+                "INSERT INTO api_keys (name, owner_email, key_hash) VALUES (?1, ?2, ?3) RETURNING id",
+                (&name, &owner_email, &key_hash),
+                |row| row.get(0),
+            )
+        })
+        .await
+    }
+
+    async fn find_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, DbError> {
+        let key_hash = key_hash.to_string();
+        pool::run(&self.pool, move |db| {
+            db.query_row(
+                "SELECT owner_email FROM api_keys WHERE key_hash = ?1 AND revoked_at IS NULL",
+                [&key_hash],
+                |row| {
+                    Ok(ApiKey {
+                        owner_email: row.get(0)?,
+                    })
+                },
+            )
+            .optional()
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl WebhookRepository for SqliteRepository {
+    async fn enqueue(&self, url_id: i64, webhook_url: &str, payload: &str) -> Result<i64, DbError> {
+        let (webhook_url, payload) = (webhook_url.to_string(), payload.to_string());
+        let now = Utc::now();
+        pool::run(&self.pool, move |db| {
+            db.query_row(
+                "INSERT INTO webhook_deliveries (url_id, webhook_url, payload, next_attempt_at, created_at) VALUES (?1, ?2, ?3, ?4, ?4) RETURNING id",
+                (url_id, &webhook_url, &payload, now),
+                |row| row.get(0),
+            )
+        })
+        .await
+    }
+
+    async fn claim_due(
+        &self,
+        now: DateTime<Utc>,
+        lease_until: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, DbError> {
+        pool::run(&self.pool, move |db| {
+            db.prepare(&format!(
+                "UPDATE webhook_deliveries SET next_attempt_at = ?2 WHERE id IN (SELECT id FROM webhook_deliveries WHERE status = 'pending' AND next_attempt_at <= ?1 ORDER BY next_attempt_at LIMIT ?3) RETURNING {DELIVERY_COLUMNS}"
+            ))?
+            .query_map((now, lease_until, limit), delivery_from_row)?
+            .collect()
+        })
+        .await
+    }
+
+    async fn mark_delivered(&self, id: i64, attempts: i32) -> Result<(), DbError> {
+        let now = Utc::now();
+        pool::run(&self.pool, move |db| {
+            db.execute(
+                "UPDATE webhook_deliveries SET status = 'delivered', attempts = ?2, delivered_at = ?3, last_error = NULL WHERE id = ?1",
+                (id, attempts, now),
+            )
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn mark_retry(
+        &self,
+        id: i64,
+        attempts: i32,
+        next_attempt_at: DateTime<Utc>,
+        error: &str,
+    ) -> Result<(), DbError> {
+        let error = error.to_string();
+        pool::run(&self.pool, move |db| {
+            db.execute(
+                "UPDATE webhook_deliveries SET attempts = ?2, next_attempt_at = ?3, last_error = ?4 WHERE id = ?1",
+                (id, attempts, next_attempt_at, &error),
+            )
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn mark_failed(&self, id: i64, attempts: i32, error: &str) -> Result<(), DbError> {
+        let error = error.to_string();
+        pool::run(&self.pool, move |db| {
+            db.execute(
+                "UPDATE webhook_deliveries SET status = 'failed', attempts = ?2, last_error = ?3 WHERE id = ?1",
+                (id, attempts, &error),
+            )
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn list_failed(
+        &self,
+        url_id: i64,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<WebhookDelivery>, DbError> {
+        pool::run(&self.pool, move |db| {
+            db.prepare(&format!(
+                "SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries WHERE url_id = ?1 AND status = 'failed' ORDER BY id DESC LIMIT ?2 OFFSET ?3"
+            ))?
+            .query_map((url_id, limit, offset), delivery_from_row)?
+            .collect()
+        })
+        .await
+    }
+
+    async fn count_failed(&self, url_id: i64) -> Result<i64, DbError> {
+        pool::run(&self.pool, move |db| {
+            db.query_row(
+                "SELECT COUNT(*) FROM webhook_deliveries WHERE url_id = ?1 AND status = 'failed'",
+                [url_id],
+                |row| row.get(0),
+            )
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl ClickRepository for SqliteRepository {
+    async fn record(&self, event: ClickEvent) -> Result<(), DbError> {
+        let visitor_hash = event.visitor_hash.clone();
+        pool::run(&self.pool, move |db| {
+            let tx = db.unchecked_transaction()?;
+            for interval in [ClickInterval::Hour, ClickInterval::Day] {
+                let bucket_start = interval.truncate(event.occurred_at);
+                // 같은 구간에 이미 접속한 방문자면 순 방문자 수는 그대로 둠
+                let seen: bool = tx.query_row(
+                    "SELECT EXISTS (SELECT 1 FROM clicks WHERE url_id = ?1 AND visitor_hash = ?2 AND occurred_at >= ?3 AND occurred_at < ?4)",
+                    (event.url_id, &visitor_hash, bucket_start, bucket_start + interval.step()),
+                    |row| row.get(0),
+                )?;
+                let table = interval.rollup_table();
+                tx.execute(
+                    &format!(
+                        "INSERT INTO {table} (url_id, bucket_start, clicks, unique_clicks) VALUES (?1, ?2, 1, ?3) ON CONFLICT (url_id, bucket_start) DO UPDATE SET clicks = {table}.clicks + 1, unique_clicks = {table}.unique_clicks + excluded.unique_clicks"
+                    ),
+                    (event.url_id, bucket_start, i64::from(!seen)),
+                )?;
+            }
+            tx.execute(
+                "INSERT INTO clicks (url_id, occurred_at, platform, visitor_hash, referer, accept_language, query, destination) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                (event.url_id, event.occurred_at, event.platform.as_str(), &visitor_hash, &event.referer, &event.accept_language, &event.query, &event.destination),
+            )?;
+            tx.commit()
+        })
+        .await
+    }
+
+    async fn count(
+        &self,
+        url_id: i64,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<(i64, i64), DbError> {
+        pool::run(&self.pool, move |db| {
+            db.query_row(
+                "SELECT COUNT(*), COUNT(DISTINCT visitor_hash) FROM clicks WHERE url_id = ?1 AND occurred_at >= ?2 AND occurred_at < ?3",
+                (url_id, from, to),
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+        })
+        .await
+    }
+
+    async fn count_by_platform(
+        &self,
+        url_id: i64,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<(String, i64)>, DbError> {
+        pool::run(&self.pool, move |db| {
+            db.prepare(
+                "SELECT platform, COUNT(*) FROM clicks WHERE url_id = ?1 AND occurred_at >= ?2 AND occurred_at < ?3 GROUP BY platform",
+            )?
+            .query_map((url_id, from, to), |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect()
+        })
+        .await
+    }
+
+    async fn top_referers(
+        &self,
+        url_id: i64,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<(String, i64)>, DbError> {
+        pool::run(&self.pool, move |db| {
+            db.prepare(
+                "SELECT referer, COUNT(*) AS hits FROM clicks WHERE url_id = ?1 AND occurred_at >= ?2 AND occurred_at < ?3 AND referer IS NOT NULL GROUP BY referer ORDER BY hits DESC, referer LIMIT ?4",
+            )?
+            .query_map((url_id, from, to, limit), |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect()
+        })
+        .await
+    }
+
+    async fn time_series(
+        &self,
+        url_id: i64,
+        interval: ClickInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<ClickBucket>, DbError> {
+        pool::run(&self.pool, move |db| {
+            db.prepare(&format!(
+                "SELECT bucket_start, clicks, unique_clicks FROM {} WHERE url_id = ?1 AND bucket_start >= ?2 AND bucket_start < ?3 ORDER BY bucket_start",
+                interval.rollup_table()
+            ))?
+            .query_map((url_id, from, to), |row| {
+                Ok(ClickBucket {
+                    bucket_start: row.get(0)?,
+                    clicks: row.get(1)?,
+                    unique_clicks: row.get(2)?,
+                })
+            })?
+            .collect()
+        })
+        .await
+    }
+}
@@ -1 +1,5 @@
+pub mod password_schemas;
 pub mod short_url_schemas;
+pub mod stats_schemas;
+pub mod webhook_schemas;
+pub mod well_known_schemas;
@@ -0,0 +1,7 @@
+use serde::Deserialize;
+
+// 비밀번호 입력 폼
+#[derive(Deserialize)]
+pub struct UnlockForm {
+    pub password: String,
+}
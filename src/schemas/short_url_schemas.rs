@@ -1,8 +1,13 @@
-use serde::{Deserialize, Serialize};
+use crate::models::repository::Url;
+use crate::utils::short_url::app_url;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
 
 // URL 요청 구조체
 #[derive(Deserialize)]
 pub struct CreateUrlRequest {
+    // API 키로 인증된 요청에서는 생략 가능 (키 소유자 이메일 사용)
+    #[serde(default)]
     pub email: String,
     #[serde(rename = "iosDeepLink")]
     pub ios_deep_link: String,
@@ -12,16 +17,235 @@ pub struct CreateUrlRequest {
     pub android_deep_link: String,
     #[serde(rename = "androidFallbackUrl")]
     pub android_fallback_url: String,
+    // intent:// URL 에 넣을 앱 패키지 이름 (예: com.example.app)
+    #[serde(rename = "androidPackageName", default)]
+    pub android_package_name: String,
     #[serde(rename = "defaultFallbackUrl")]
     pub default_fallback_url: String,
     #[serde(rename = "webhookUrl")]
     pub webhook_url: String,
     #[serde(rename = "headHtml")]
     pub head_html: String,
+    // 301, 302, 307, 308 중 하나 (기본값 302)
+    #[serde(rename = "redirectStatus")]
+    pub redirect_status: Option<i32>,
+    // 접속 가능 기간과 최대 접속 횟수 (모두 선택)
+    #[serde(rename = "activatesAt")]
+    pub activates_at: Option<DateTime<Utc>>,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(rename = "maxClicks")]
+    pub max_clicks: Option<i32>,
+    // 만료 시 동작 gone, page, redirect (기본값은 expiredFallbackUrl 이 있으면 redirect, 없으면 gone)
+    #[serde(rename = "expiredAction")]
+    pub expired_action: Option<String>,
+    #[serde(rename = "expiredFallbackUrl")]
+    pub expired_fallback_url: Option<String>,
+    // 설정하면 접속 시 비밀번호 입력 필요
+    pub password: Option<String>,
+    // 생성된 키 대신 사용할 단축 키 (예: spring-sale)
+    pub alias: Option<String>,
+    // 미리보기용 메타데이터 (headHtml 과 별도)
+    #[serde(rename = "openGraph", default)]
+    pub open_graph: OpenGraphRequest,
+}
+
+// 미리보기용 메타데이터 요청 (수정 시에는 전달된 필드만 변경, 빈 문자열이면 삭제)
+#[derive(Deserialize, Default)]
+pub struct OpenGraphRequest {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    // summary, summary_large_image, app, player 중 하나
+    #[serde(rename = "twitterCard")]
+    pub twitter_card: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct OpenGraphResponse {
+    pub title: String,
+    pub description: String,
+    pub image: String,
+    #[serde(rename = "twitterCard")]
+    pub twitter_card: String,
 }
 
 // URL 응답 구조체
 #[derive(Serialize)]
 pub struct CreateUrlResponse {
+    // 이전 클라이언트 호환용 (snake_case 유지)
     pub is_created: bool,
+    #[serde(rename = "shortKey")]
+    pub short_key: String,
+    #[serde(rename = "shortUrl")]
+    pub short_url: String,
+    // 앱으로 바로 열리는 주소 (딥링크가 있는 경우에만)
+    #[serde(rename = "appUrl", skip_serializing_if = "Option::is_none")]
+    pub app_url: Option<String>,
+    #[serde(rename = "isVerified")]
+    pub is_verified: bool,
+    // 인증 메일의 코드 만료 시각 (이미 인증된 경우 null)
+    #[serde(rename = "verificationExpiresAt")]
+    pub verification_expires_at: Option<DateTime<Utc>>,
+    // 웹훅 서명 키, 새로 생성된 경우에만 한 번 반환
+    #[serde(rename = "webhookSecret", skip_serializing_if = "Option::is_none")]
+    pub webhook_secret: Option<String>,
+}
+
+// URL 수정 요청 구조체 (전달된 필드만 변경)
+#[derive(Deserialize)]
+pub struct UpdateUrlRequest {
+    #[serde(rename = "iosDeepLink")]
+    pub ios_deep_link: Option<String>,
+    #[serde(rename = "iosFallbackUrl")]
+    pub ios_fallback_url: Option<String>,
+    #[serde(rename = "androidDeepLink")]
+    pub android_deep_link: Option<String>,
+    #[serde(rename = "androidFallbackUrl")]
+    pub android_fallback_url: Option<String>,
+    #[serde(rename = "androidPackageName")]
+    pub android_package_name: Option<String>,
+    #[serde(rename = "defaultFallbackUrl")]
+    pub default_fallback_url: Option<String>,
+    #[serde(rename = "webhookUrl")]
+    pub webhook_url: Option<String>,
+    #[serde(rename = "headHtml")]
+    pub head_html: Option<String>,
+    #[serde(rename = "redirectStatus")]
+    pub redirect_status: Option<i32>,
+    // 필드가 없으면 None (기존 값 유지), null 이면 Some(None) (제한 해제)
+    #[serde(rename = "activatesAt", default, deserialize_with = "double_option")]
+    pub activates_at: Option<Option<DateTime<Utc>>>,
+    #[serde(rename = "expiresAt", default, deserialize_with = "double_option")]
+    pub expires_at: Option<Option<DateTime<Utc>>>,
+    #[serde(rename = "maxClicks", default, deserialize_with = "double_option")]
+    pub max_clicks: Option<Option<i32>>,
+    #[serde(rename = "expiredAction")]
+    pub expired_action: Option<String>,
+    #[serde(rename = "expiredFallbackUrl")]
+    pub expired_fallback_url: Option<String>,
+    // 빈 문자열이면 비밀번호 해제
+    pub password: Option<String>,
+    #[serde(rename = "openGraph")]
+    pub open_graph: Option<OpenGraphRequest>,
+}
+
+// 필드가 있으면 null 이어도 Some 으로 감싸 생략과 구분 (#[serde(default)] 와 함께 사용)
+fn double_option<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+// URL 목록 조회 쿼리
+#[derive(Deserialize)]
+pub struct ListUrlsQuery {
+    pub page: Option<i64>,
+    pub size: Option<i64>,
+}
+
+// URL 상세 응답 구조체
+#[derive(Serialize)]
+pub struct UrlResponse {
+    #[serde(rename = "shortKey")]
+    pub short_key: String,
+    pub email: String,
+    #[serde(rename = "iosDeepLink")]
+    pub ios_deep_link: String,
+    #[serde(rename = "iosFallbackUrl")]
+    pub ios_fallback_url: String,
+    #[serde(rename = "androidDeepLink")]
+    pub android_deep_link: String,
+    #[serde(rename = "androidFallbackUrl")]
+    pub android_fallback_url: String,
+    #[serde(rename = "androidPackageName")]
+    pub android_package_name: String,
+    #[serde(rename = "defaultFallbackUrl")]
+    pub default_fallback_url: String,
+    #[serde(rename = "webhookUrl")]
+    pub webhook_url: String,
+    #[serde(rename = "headHtml")]
+    pub head_html: String,
+    #[serde(rename = "openGraph")]
+    pub open_graph: OpenGraphResponse,
+    #[serde(rename = "redirectStatus")]
+    pub redirect_status: i32,
+    #[serde(rename = "isVerified")]
+    pub is_verified: bool,
+    #[serde(rename = "activatesAt")]
+    pub activates_at: Option<DateTime<Utc>>,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(rename = "maxClicks")]
+    pub max_clicks: Option<i32>,
+    #[serde(rename = "clickCount")]
+    pub click_count: i32,
+    #[serde(rename = "expiredAction")]
+    pub expired_action: String,
+    #[serde(rename = "expiredFallbackUrl")]
+    pub expired_fallback_url: String,
+    #[serde(rename = "hasPassword")]
+    pub has_password: bool,
+    // 앱으로 바로 열리는 주소 (딥링크가 있는 경우에만)
+    #[serde(rename = "appUrl", skip_serializing_if = "Option::is_none")]
+    pub app_url: Option<String>,
+}
+
+// URL 목록 응답 구조체
+#[derive(Serialize)]
+pub struct ListUrlsResponse {
+    pub items: Vec<UrlResponse>,
+    pub page: i64,
+    pub size: i64,
+    pub total: i64,
+}
+
+impl From<Url> for UrlResponse {
+    fn from(url: Url) -> Self {
+        let short_key = url.short_key();
+        UrlResponse {
+            app_url: app_url(&short_key, &url.ios_deep_link, &url.android_deep_link),
+            short_key,
+            email: url.email,
+            ios_deep_link: url.ios_deep_link,
+            ios_fallback_url: url.ios_fallback_url,
+            android_deep_link: url.android_deep_link,
+            android_fallback_url: url.android_fallback_url,
+            android_package_name: url.android_package_name,
+            default_fallback_url: url.default_fallback_url,
+            webhook_url: url.webhook_url,
+            head_html: url.head_html,
+            open_graph: OpenGraphResponse {
+                title: url.og_title,
+                description: url.og_description,
+                image: url.og_image,
+                twitter_card: url.twitter_card,
+            },
+            redirect_status: url.redirect_status,
+            is_verified: url.is_verified,
+            activates_at: url.activates_at,
+            expires_at: url.expires_at,
+            max_clicks: url.max_clicks,
+            click_count: url.click_count,
+            expired_action: url.expired_action,
+            expired_fallback_url: url.expired_fallback_url,
+            has_password: url.password_hash.is_some(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_distinguishes_null_from_missing() {
+        let request: UpdateUrlRequest =
+            serde_json::from_str(r#"{"expiresAt": null, "maxClicks": 3}"#).unwrap();
+        assert_eq!(request.expires_at, Some(None));
+        assert_eq!(request.max_clicks, Some(Some(3)));
+        assert_eq!(request.activates_at, None);
+    }
 }
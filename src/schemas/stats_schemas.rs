@@ -0,0 +1,53 @@
+use crate::models::click_event::ClickInterval;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// 접속 통계 조회 쿼리 (기간은 [from, to), 기본값은 최근 7일)
+#[derive(Deserialize)]
+pub struct ClickStatsQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub interval: Option<ClickInterval>,
+}
+
+// 플랫폼별 접속 수
+#[derive(Serialize, Default)]
+pub struct PlatformBreakdown {
+    pub ios: i64,
+    pub android: i64,
+    pub other: i64,
+}
+
+#[derive(Serialize)]
+pub struct RefererCount {
+    pub referer: String,
+    pub clicks: i64,
+}
+
+#[derive(Serialize)]
+pub struct ClickBucketResponse {
+    #[serde(rename = "bucketStart")]
+    pub bucket_start: DateTime<Utc>,
+    pub clicks: i64,
+    #[serde(rename = "uniqueClicks")]
+    pub unique_clicks: i64,
+}
+
+// 접속 통계 응답 구조체
+#[derive(Serialize)]
+pub struct ClickStatsResponse {
+    #[serde(rename = "shortKey")]
+    pub short_key: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub interval: ClickInterval,
+    #[serde(rename = "totalClicks")]
+    pub total_clicks: i64,
+    #[serde(rename = "uniqueClicks")]
+    pub unique_clicks: i64,
+    pub platforms: PlatformBreakdown,
+    #[serde(rename = "topReferers")]
+    pub top_referers: Vec<RefererCount>,
+    #[serde(rename = "timeSeries")]
+    pub time_series: Vec<ClickBucketResponse>,
+}
@@ -0,0 +1,51 @@
+use crate::models::repository::WebhookDelivery;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// 웹훅 전송 내역 조회 쿼리
+#[derive(Deserialize)]
+pub struct ListWebhookDeliveriesQuery {
+    pub page: Option<i64>,
+    pub size: Option<i64>,
+}
+
+// 웹훅 전송 내역 응답 구조체
+#[derive(Serialize)]
+pub struct WebhookDeliveryResponse {
+    pub id: i64,
+    #[serde(rename = "webhookUrl")]
+    pub webhook_url: String,
+    pub payload: serde_json::Value,
+    pub status: String,
+    pub attempts: i32,
+    #[serde(rename = "lastError")]
+    pub last_error: Option<String>,
+    #[serde(rename = "nextAttemptAt")]
+    pub next_attempt_at: DateTime<Utc>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+// 웹훅 전송 내역 목록 응답 구조체
+#[derive(Serialize)]
+pub struct ListWebhookDeliveriesResponse {
+    pub items: Vec<WebhookDeliveryResponse>,
+    pub page: i64,
+    pub size: i64,
+    pub total: i64,
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryResponse {
+    fn from(delivery: WebhookDelivery) -> Self {
+        WebhookDeliveryResponse {
+            id: delivery.id,
+            webhook_url: delivery.webhook_url,
+            payload: serde_json::from_str(&delivery.payload).unwrap_or(serde_json::Value::Null),
+            status: delivery.status,
+            attempts: delivery.attempts,
+            last_error: delivery.last_error,
+            next_attempt_at: delivery.next_attempt_at,
+            created_at: delivery.created_at,
+        }
+    }
+}
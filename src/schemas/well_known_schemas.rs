@@ -0,0 +1,42 @@
+use serde::Serialize;
+
+// /.well-known/apple-app-site-association
+#[derive(Serialize)]
+pub struct AppleAppSiteAssociation {
+    pub applinks: AppLinks,
+}
+
+#[derive(Serialize)]
+pub struct AppLinks {
+    pub details: Vec<AppLinksDetail>,
+}
+
+#[derive(Serialize)]
+pub struct AppLinksDetail {
+    #[serde(rename = "appIDs")]
+    pub app_ids: Vec<String>,
+    pub components: Vec<AppLinksComponent>,
+}
+
+// 위에서부터 처음 일치하는 규칙이 적용됨
+#[derive(Serialize)]
+pub struct AppLinksComponent {
+    #[serde(rename = "/")]
+    pub path: &'static str,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub exclude: bool,
+}
+
+// /.well-known/assetlinks.json 의 항목
+#[derive(Serialize)]
+pub struct AssetLink {
+    pub relation: Vec<&'static str>,
+    pub target: AssetLinkTarget,
+}
+
+#[derive(Serialize)]
+pub struct AssetLinkTarget {
+    pub namespace: &'static str,
+    pub package_name: String,
+    pub sha256_cert_fingerprints: Vec<String>,
+}
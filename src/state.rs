@@ -1,14 +1,51 @@
-use rusqlite::Connection;
-use std::collections::HashMap;
+use crate::models::repository::{
+    ApiKeyRepository, ClickRepository, EmailAuthRepository, UrlRepository, WebhookRepository,
+};
+use crate::utils::app_links::AppLinksConfig;
+use crate::utils::key_codec::KeyCodec;
+use crate::utils::url_safety::UrlSafety;
+use crate::utils::webhook_policy::WebhookPolicy;
+use dashmap::DashMap;
+use ipnet::IpNet;
+use std::sync::Arc;
 use std::time::Instant;
-use tokio::sync::RwLock;
+use tokio::sync::Notify;
 
 pub struct CacheEntry {
     pub data: String,
     pub expiry: Instant,
 }
 
+// 링크별 비밀번호 입력 실패 횟수
+pub struct FailedAttempts {
+    pub count: u32,
+    pub window_start: Instant,
+}
+
+// 핸들러 간 공유 상태
+// 전체를 감싸는 잠금 없이 Arc 로만 공유하며, 각 필드가 자체적으로 동시 접근을 처리
 pub struct AppState {
-    pub db: Connection,
-    pub cache: RwLock<HashMap<String, CacheEntry>>,
+    pub urls: Arc<dyn UrlRepository>,
+    pub email_auth: Arc<dyn EmailAuthRepository>,
+    pub api_keys: Arc<dyn ApiKeyRepository>,
+    pub webhooks: Arc<dyn WebhookRepository>,
+    pub clicks: Arc<dyn ClickRepository>,
+    // 웹훅 대기열에 새 건이 추가되면 워커를 깨움
+    pub webhook_notify: Notify,
+    pub cache: DashMap<String, CacheEntry>,
+    // X-Forwarded-For 를 신뢰할 프록시 주소 대역
+    pub trusted_proxies: Vec<IpNet>,
+    // 비밀번호 잠금 해제 쿠키 서명 키
+    pub cookie_secret: String,
+    // 순 방문자 해시 키
+    pub visitor_secret: String,
+    pub password_attempts: DashMap<i64, FailedAttempts>,
+    // 새 URL 의 단축 키 인코딩 방식
+    pub key_codec: Box<dyn KeyCodec>,
+    // apple-app-site-association, assetlinks.json 에 들어갈 앱 정보
+    pub app_links: AppLinksConfig,
+    // 웹훅 목적지 허용/차단 정책 (생성 시와 전송 시 모두 검사)
+    pub webhook_policy: WebhookPolicy,
+    // 목적지 URL 안전성 검사 (차단 목록, 외부 평판 서비스)
+    pub url_safety: UrlSafety,
 }
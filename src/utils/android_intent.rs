@@ -0,0 +1,72 @@
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+
+// Chrome 의 intent:// URL 생성
+// 앱이 있으면 앱을 열고, 없으면 fallback_url 로 이동 (fallback_url 이 없고 패키지 이름이 있으면 Play 스토어)
+// 예: myapp://product/1 -> intent://product/1#Intent;scheme=myapp;package=com.example.app;end
+// 변환할 수 없는 딥링크 (scheme:path 형태 등) 는 None
+pub fn android_intent_url(
+    deep_link: &str,
+    package_name: &str,
+    fallback_url: &str,
+) -> Option<String> {
+    if deep_link.starts_with("intent:") {
+        return Some(deep_link.to_string());
+    }
+    let (scheme, rest) = deep_link.split_once("://")?;
+    if !is_scheme(scheme) {
+        return None;
+    }
+    // '#' 뒤는 Intent 파라미터 자리이므로 딥링크의 fragment 는 버림
+    let rest = rest.split('#').next().unwrap_or("");
+
+    let mut intent = format!("intent://{}#Intent;scheme={};", rest, scheme);
+    if !package_name.is_empty() {
+        intent.push_str(&format!("package={};", package_name));
+    }
+    if !fallback_url.is_empty() {
+        intent.push_str(&format!(
+            "S.browser_fallback_url={};",
+            utf8_percent_encode(fallback_url, NON_ALPHANUMERIC)
+        ));
+    }
+    intent.push_str("end");
+    Some(intent)
+}
+
+// RFC 3986 scheme: 영문자로 시작하고 영문자, 숫자, '+', '-', '.' 로 구성
+fn is_scheme(scheme: &str) -> bool {
+    let mut chars = scheme.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_schemes_become_intent_urls() {
+        assert_eq!(
+            android_intent_url("myapp://product/1#top", "com.example.app", "").as_deref(),
+            Some("intent://product/1#Intent;scheme=myapp;package=com.example.app;end")
+        );
+        assert_eq!(
+            android_intent_url("myapp://product/1", "", "https://example.com/a?b=1").as_deref(),
+            Some(
+                "intent://product/1#Intent;scheme=myapp;\
+                 S.browser_fallback_url=https%3A%2F%2Fexample%2Ecom%2Fa%3Fb%3D1;end"
+            )
+        );
+    }
+
+    #[test]
+    fn other_links_are_kept_or_refused() {
+        let intent = "intent://product/1#Intent;scheme=myapp;end";
+        assert_eq!(
+            android_intent_url(intent, "com.example.app", "").as_deref(),
+            Some(intent)
+        );
+        assert_eq!(android_intent_url("myapp:product/1", "", ""), None);
+        assert_eq!(android_intent_url("1app://product/1", "", ""), None);
+    }
+}
@@ -0,0 +1,117 @@
+use std::env;
+
+// Universal Links / App Links 설정
+// 예: APPLE_TEAM_ID=ABCDE12345, APPLE_BUNDLE_IDS=com.example.app
+//     ANDROID_PACKAGE_NAMES=com.example.app, ANDROID_SHA256_CERT_FINGERPRINTS=14:6D:E9:...
+pub struct AppLinksConfig {
+    // "<팀 ID>.<번들 ID>" 형태의 iOS 앱 ID
+    pub apple_app_ids: Vec<String>,
+    pub android_package_names: Vec<String>,
+    // 대문자 16진수를 ':' 로 구분한 서명 인증서 SHA-256 지문
+    pub android_fingerprints: Vec<String>,
+}
+
+impl AppLinksConfig {
+    pub fn from_env() -> Self {
+        fn list(key: &str) -> Vec<String> {
+            env::var(key)
+                .unwrap_or("".to_string())
+                .split(',')
+                .map(str::trim)
+                .filter(|entry| !entry.is_empty())
+                .map(str::to_string)
+                .collect()
+        }
+
+        // 팀 ID 와 번들 ID 를 조합하고, 다른 팀의 앱은 APPLE_APP_IDS 에 전체 앱 ID 로 지정
+        let mut apple_app_ids = list("APPLE_APP_IDS");
+        let team_id = env::var("APPLE_TEAM_ID").unwrap_or("".to_string());
+        if !team_id.is_empty() {
+            apple_app_ids.extend(
+                list("APPLE_BUNDLE_IDS")
+                    .into_iter()
+                    .map(|bundle_id| format!("{}.{}", team_id, bundle_id)),
+            );
+        }
+        apple_app_ids.retain(|app_id| {
+            let valid = is_apple_app_id(app_id);
+            if !valid {
+                println!("iOS 앱 ID 형식이 올바르지 않습니다: {}", app_id);
+            }
+            valid
+        });
+
+        let android_fingerprints = list("ANDROID_SHA256_CERT_FINGERPRINTS")
+            .into_iter()
+            .filter_map(|entry| {
+                let fingerprint = normalize_fingerprint(&entry);
+                if fingerprint.is_none() {
+                    println!("SHA-256 인증서 지문 형식이 올바르지 않습니다: {}", entry);
+                }
+                fingerprint
+            })
+            .collect();
+
+        AppLinksConfig {
+            apple_app_ids,
+            android_package_names: list("ANDROID_PACKAGE_NAMES"),
+            android_fingerprints,
+        }
+    }
+}
+
+// 팀 ID (영문 대문자/숫자 10자) + '.' + 번들 ID
+fn is_apple_app_id(app_id: &str) -> bool {
+    match app_id.split_once('.') {
+        Some((team_id, bundle_id)) => {
+            team_id.len() == 10
+                && team_id
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+                && !bundle_id.is_empty()
+        }
+        None => false,
+    }
+}
+
+// ':' 유무와 대소문자에 상관없이 받아서 "AB:CD:..." 형태로 변환
+fn normalize_fingerprint(value: &str) -> Option<String> {
+    let hex: String = value.chars().filter(|&c| c != ':').collect();
+    if hex.len() != 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let bytes: Vec<String> = hex
+        .to_ascii_uppercase()
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| String::from_utf8_lossy(pair).into_owned())
+        .collect();
+    Some(bytes.join(":"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apple_app_ids_need_team_and_bundle() {
+        assert!(is_apple_app_id("ABCDE12345.com.example.app"));
+        assert!(!is_apple_app_id("abcde12345.com.example.app"));
+        assert!(!is_apple_app_id("ABCDE1234.com.example.app"));
+        assert!(!is_apple_app_id("ABCDE12345."));
+        assert!(!is_apple_app_id("com.example.app"));
+        assert!(!is_apple_app_id("ABCDE12345"));
+    }
+
+    #[test]
+    fn fingerprints_are_normalized() {
+        let hex = "146de983c5730650d1dd1a4ab4d9d4f2a1d2c3b4e5f60718293a4b5c6d7e8f90";
+        let expected = "14:6D:E9:83:C5:73:06:50:D1:DD:1A:4A:B4:D9:D4:F2:\
+                        A1:D2:C3:B4:E5:F6:07:18:29:3A:4B:5C:6D:7E:8F:90";
+        assert_eq!(normalize_fingerprint(hex).as_deref(), Some(expected));
+        // ':' 로 구분한 값도 같은 결과
+        assert_eq!(normalize_fingerprint(expected).as_deref(), Some(expected));
+        assert_eq!(normalize_fingerprint(&hex[2..]), None);
+        assert_eq!(normalize_fingerprint(&hex.replace('1', "g")), None);
+    }
+}
@@ -0,0 +1,126 @@
+use axum::http::HeaderMap;
+use ipnet::IpNet;
+use std::env;
+use std::net::IpAddr;
+
+// 신뢰할 프록시 목록 (TRUSTED_PROXIES, 쉼표로 구분한 IP 또는 CIDR)
+// 예: TRUSTED_PROXIES=127.0.0.1,10.0.0.0/8
+pub fn trusted_proxies_from_env() -> Vec<IpNet> {
+    let value = env::var("TRUSTED_PROXIES").unwrap_or("".to_string());
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| {
+            let parsed = entry
+                .parse::<IpNet>()
+                .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from));
+            if parsed.is_err() {
+                println!("TRUSTED_PROXIES 항목을 해석할 수 없습니다: {}", entry);
+            }
+            parsed.ok()
+        })
+        .collect()
+}
+
+// 실제 클라이언트 IP
+// 직접 연결한 상대가 신뢰할 프록시일 때만 X-Forwarded-For 를 오른쪽부터 따라가며
+// 신뢰할 프록시가 아닌 첫 주소를 사용 (왼쪽 값은 클라이언트가 위조할 수 있음)
+pub fn client_ip(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &[IpNet]) -> IpAddr {
+    let is_trusted = |ip: &IpAddr| trusted_proxies.iter().any(|net| net.contains(ip));
+    if !is_trusted(&peer) {
+        return peer;
+    }
+    let forwarded: Vec<&str> = headers
+        .get_all("X-Forwarded-For")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect();
+    let mut client = peer;
+    for entry in forwarded.into_iter().rev() {
+        // 해석할 수 없는 항목부터 왼쪽은 신뢰할 수 없으므로 마지막으로 확인한 주소 사용
+        let Ok(ip) = entry.parse::<IpAddr>() else {
+            break;
+        };
+        client = ip;
+        if !is_trusted(&ip) {
+            break;
+        }
+    }
+    client
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forwarded_for(values: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append("X-Forwarded-For", value.parse().unwrap());
+        }
+        headers
+    }
+
+    fn proxies(entries: &[&str]) -> Vec<IpNet> {
+        entries.iter().map(|entry| entry.parse().unwrap()).collect()
+    }
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn untrusted_peer_ignores_forwarded_for() {
+        let trusted = proxies(&["10.0.0.0/8"]);
+        let headers = forwarded_for(&["1.2.3.4"]);
+        assert_eq!(client_ip(ip("8.8.8.8"), &headers, &trusted), ip("8.8.8.8"));
+        // 신뢰할 프록시가 없으면 항상 직접 연결한 주소
+        assert_eq!(client_ip(ip("10.0.0.1"), &headers, &[]), ip("10.0.0.1"));
+    }
+
+    #[test]
+    fn follows_chain_of_trusted_proxies() {
+        let trusted = proxies(&["10.0.0.0/8", "192.168.0.1/32"]);
+        let headers = forwarded_for(&["1.2.3.4, 192.168.0.1", "10.0.0.2"]);
+        assert_eq!(client_ip(ip("10.0.0.1"), &headers, &trusted), ip("1.2.3.4"));
+        // 헤더가 없으면 직접 연결한 프록시 주소
+        assert_eq!(
+            client_ip(ip("10.0.0.1"), &HeaderMap::new(), &trusted),
+            ip("10.0.0.1")
+        );
+    }
+
+    #[test]
+    fn spoofed_left_entries_are_ignored() {
+        let trusted = proxies(&["10.0.0.0/8"]);
+        // 클라이언트가 보낸 값 (9.9.9.9) 뒤에 프록시가 실제 주소를 덧붙임
+        let headers = forwarded_for(&["9.9.9.9, 1.2.3.4, 10.0.0.2"]);
+        assert_eq!(client_ip(ip("10.0.0.1"), &headers, &trusted), ip("1.2.3.4"));
+        // 신뢰할 프록시 주소로 위조해도 그 오른쪽의 실제 주소에서 멈춤
+        let headers = forwarded_for(&["10.0.0.9, 1.2.3.4"]);
+        assert_eq!(client_ip(ip("10.0.0.1"), &headers, &trusted), ip("1.2.3.4"));
+    }
+
+    #[test]
+    fn malformed_entries_stop_the_chain() {
+        let trusted = proxies(&["10.0.0.0/8"]);
+        let headers = forwarded_for(&["1.2.3.4, unknown, 10.0.0.2"]);
+        assert_eq!(
+            client_ip(ip("10.0.0.1"), &headers, &trusted),
+            ip("10.0.0.2")
+        );
+        let headers = forwarded_for(&["1.2.3.4:5678"]);
+        assert_eq!(
+            client_ip(ip("10.0.0.1"), &headers, &trusted),
+            ip("10.0.0.1")
+        );
+        let headers = forwarded_for(&[""]);
+        assert_eq!(
+            client_ip(ip("10.0.0.1"), &headers, &trusted),
+            ip("10.0.0.1")
+        );
+    }
+}
@@ -1,28 +1,127 @@
-pub fn id_to_key(id: i64) -> String {
-    let chars = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
-    let mut key = String::new();
+use std::fmt;
+
+const CHARS: &[u8; 62] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+// random_key 앞 2자 + id 1자 이상 + random_key 뒤 2자
+const MIN_SHORT_KEY_LEN: usize = 5;
+
+// 단축 키 해석 오류
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyError {
+    Empty,
+    TooShort,
+    InvalidChar(char),
+    Overflow,
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyError::Empty => write!(f, "키가 비어 있습니다"),
+            KeyError::TooShort => write!(f, "단축 키가 너무 짧습니다"),
+            KeyError::InvalidChar(c) => write!(f, "키에 사용할 수 없는 문자입니다: {:?}", c),
+            KeyError::Overflow => write!(f, "키가 나타내는 id 가 너무 큽니다"),
+        }
+    }
+}
+
+impl std::error::Error for KeyError {}
+
+pub fn id_to_key(id: u64) -> String {
+    let base = CHARS.len() as u64;
+    let mut key = Vec::new();
     let mut id = id;
-    while id > 0 {
-        key.push(chars.chars().nth(id as usize % chars.len()).unwrap());
-        id /= chars.len() as i64;
+    loop {
+        key.push(CHARS[(id % base) as usize]);
+        id /= base;
+        if id == 0 {
+            break;
+        }
     }
-    key.chars().rev().collect()
+    key.reverse();
+    // CHARS 는 모두 ASCII 이므로 항상 유효한 UTF-8
+    String::from_utf8(key).unwrap_or_default()
 }
 
-pub fn key_to_id(key: &str) -> i64 {
-    let chars = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
-    let mut id = 0;
+pub fn key_to_id(key: &str) -> Result<u64, KeyError> {
+    if key.is_empty() {
+        return Err(KeyError::Empty);
+    }
+    let mut id: u64 = 0;
     for c in key.chars() {
-        id = id * chars.len() as i64 + chars.find(c).unwrap() as i64;
+        let digit = CHARS
+            .iter()
+            .position(|&b| b as char == c)
+            .ok_or(KeyError::InvalidChar(c))?;
+        id = id
+            .checked_mul(CHARS.len() as u64)
+            .and_then(|id| id.checked_add(digit as u64))
+            .ok_or(KeyError::Overflow)?;
+    }
+    Ok(id)
+}
+
+// 생성된 단축 키를 (url id, random_key) 로 분리
+pub fn split_short_key(short_key: &str) -> Result<(i64, String), KeyError> {
+    // 멀티바이트 문자가 섞이면 바이트 단위로 자를 수 없으므로 먼저 검사
+    if let Some(c) = short_key.chars().find(|c| !c.is_ascii_alphanumeric()) {
+        return Err(KeyError::InvalidChar(c));
+    }
+    if short_key.len() < MIN_SHORT_KEY_LEN {
+        return Err(KeyError::TooShort);
     }
-    id
+    let front_random_key = &short_key[..2];
+    let back_random_key = &short_key[short_key.len() - 2..];
+    let unique_key = &short_key[2..short_key.len() - 2];
+    let url_id = i64::try_from(key_to_id(unique_key)?).map_err(|_| KeyError::Overflow)?;
+    Ok((url_id, format!("{}{}", front_random_key, back_random_key)))
+}
+
+pub fn make_short_key(id: i64, random_key: &str) -> String {
+    // DB 에서 발급한 id 는 항상 양수
+    random_key[..2].to_string() + &id_to_key(id as u64) + &random_key[2..]
 }
 
-pub fn split_short_key(short_key: &str) -> (String, String) {
-    let front_random_key = short_key[..2].to_string();
-    let back_random_key = short_key[short_key.len() - 2..].to_string();
-    let random_key = &(front_random_key + &back_random_key);
-    let unique_key = short_key[2..short_key.len() - 2].to_string();
-    let url_id = key_to_id(&unique_key);
-    (url_id.to_string(), random_key.to_string())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn zero_id_has_key() {
+        assert_eq!(id_to_key(0), "a");
+        assert_eq!(key_to_id("a"), Ok(0));
+    }
+
+    #[test]
+    fn rejects_malformed_short_keys() {
+        assert_eq!(split_short_key("a"), Err(KeyError::TooShort));
+        assert_eq!(split_short_key("abcd"), Err(KeyError::TooShort));
+        assert_eq!(split_short_key("ab-cd"), Err(KeyError::InvalidChar('-')));
+        assert_eq!(split_short_key("ab한cd"), Err(KeyError::InvalidChar('한')));
+        assert_eq!(
+            split_short_key(&format!("ab{}cd", "9".repeat(20))),
+            Err(KeyError::Overflow)
+        );
+        assert_eq!(key_to_id(""), Err(KeyError::Empty));
+    }
+
+    proptest! {
+        #[test]
+        fn id_round_trips(id in any::<u64>()) {
+            prop_assert_eq!(key_to_id(&id_to_key(id)), Ok(id));
+        }
+
+        #[test]
+        fn short_key_round_trips(id in 1..=i64::MAX, random_key in "[a-zA-Z0-9]{4}") {
+            let short_key = make_short_key(id, &random_key);
+            prop_assert_eq!(split_short_key(&short_key), Ok((id, random_key)));
+        }
+
+        #[test]
+        fn arbitrary_input_never_panics(key in "\\PC*") {
+            let _ = key_to_id(&key);
+            let _ = split_short_key(&key);
+        }
+    }
 }
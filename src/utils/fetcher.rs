@@ -0,0 +1,326 @@
+use reqwest::header::{CONTENT_TYPE, LOCATION};
+use reqwest::redirect::Policy;
+use reqwest::Url;
+use scraper::{Html, Selector};
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+// 리다이렉트를 모두 따라가고 본문을 읽기까지의 전체 제한 시간
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_REDIRECTS: usize = 3;
+// head 만 필요하므로 앞부분만 읽고 나머지는 버림
+const MAX_BODY_BYTES: usize = 512 * 1024;
+
+#[derive(Debug)]
+pub enum FetchError {
+    InvalidUrl(String),
+    // 사설, 루프백, 링크 로컬 등 외부에서 접근할 수 없어야 하는 주소
+    BlockedAddress(IpAddr),
+    Resolve(std::io::Error),
+    TooManyRedirects,
+    NotHtml(String),
+    Status(u16),
+    Timeout,
+    Request(reqwest::Error),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::InvalidUrl(url) => write!(f, "가져올 수 없는 URL입니다: {}", url),
+            FetchError::BlockedAddress(ip) => write!(f, "접근이 차단된 주소입니다: {}", ip),
+            FetchError::Resolve(e) => write!(f, "DNS 조회 실패: {}", e),
+            FetchError::TooManyRedirects => write!(f, "리다이렉트가 너무 많습니다"),
+            FetchError::NotHtml(content_type) => {
+                write!(f, "HTML 이 아닌 응답입니다: {}", content_type)
+            }
+            FetchError::Status(status) => write!(f, "응답 상태 코드 오류: {}", status),
+            FetchError::Timeout => write!(f, "제한 시간 초과"),
+            FetchError::Request(e) => write!(f, "요청 실패: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+// 외부에 공개된 주소인지 (사설, 루프백, 링크 로컬, CGNAT, 멀티캐스트 등은 false)
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || a == 0
+                // 100.64.0.0/10 (CGNAT)
+                || (a == 100 && (64..128).contains(&b))
+                // 198.18.0.0/15 (벤치마크용)
+                || (a == 198 && (18..20).contains(&b))
+                || a >= 240)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ipv4) = embedded_ipv4(ip) {
+                return is_public_ip(IpAddr::V4(ipv4));
+            }
+            let segments = ip.segments();
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                // fc00::/7 (고유 로컬), fe80::/10 (링크 로컬)
+                || (segments[0] & 0xfe00) == 0xfc00
+                || (segments[0] & 0xffc0) == 0xfe80
+                // 64:ff9b:1::/48 (로컬 NAT64)
+                || segments[..3] == [0x64, 0xff9b, 1]
+                // 2001::/32 (Teredo)
+                || segments[..2] == [0x2001, 0])
+        }
+    }
+}
+
+// IPv6 주소에 담긴 IPv4 주소 (변환 후 실제로 연결되는 주소이므로 IPv4 규칙으로 검사)
+// ::ffff:0:0/96 (IPv4-mapped), 64:ff9b::/96 (NAT64), 2002::/16 (6to4), ::/96 (IPv4-compatible)
+fn embedded_ipv4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+    if let Some(ipv4) = ip.to_ipv4_mapped() {
+        return Some(ipv4);
+    }
+    let segments = ip.segments();
+    let [a, b] = [segments[6], segments[7]];
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] || segments[..6] == [0; 6] {
+        return Some(Ipv4Addr::from((u32::from(a) << 16) | u32::from(b)));
+    }
+    if segments[0] == 0x2002 {
+        return Some(Ipv4Addr::from(
+            (u32::from(segments[1]) << 16) | u32::from(segments[2]),
+        ));
+    }
+    None
+}
+
+// 호스트를 조회해 모든 주소가 is_allowed (기본은 is_public_ip) 를 통과할 때만 반환
+async fn resolve_allowed(
+    url: &Url,
+    is_allowed: fn(IpAddr) -> bool,
+) -> Result<Vec<SocketAddr>, FetchError> {
+    let host = url
+        .host_str()
+        .ok_or_else(|| FetchError::InvalidUrl(url.to_string()))?;
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| FetchError::InvalidUrl(url.to_string()))?;
+    // IPv6 리터럴은 대괄호를 벗겨서 조회
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(FetchError::Resolve)?
+        .collect();
+    if let Some(blocked) = addrs.iter().find(|addr| !is_allowed(addr.ip())) {
+        return Err(FetchError::BlockedAddress(blocked.ip()));
+    }
+    if addrs.is_empty() {
+        return Err(FetchError::InvalidUrl(url.to_string()));
+    }
+    Ok(addrs)
+}
+
+// 외부 HTML 페이지 가져오기
+// 리다이렉트마다 주소를 다시 검사하고, 검사한 주소로만 연결해 DNS 재바인딩을 막음
+pub async fn fetch_html(url: &str) -> Result<String, FetchError> {
+    fetch_html_with(url, is_public_ip).await
+}
+
+// 연결할 주소의 검사 방식을 지정해 가져오기 (테스트에서는 로컬 서버를 허용)
+async fn fetch_html_with(url: &str, is_allowed: fn(IpAddr) -> bool) -> Result<String, FetchError> {
+    tokio::time::timeout(FETCH_TIMEOUT, fetch_html_inner(url, is_allowed))
+        .await
+        .map_err(|_| FetchError::Timeout)?
+}
+
+async fn fetch_html_inner(url: &str, is_allowed: fn(IpAddr) -> bool) -> Result<String, FetchError> {
+    let mut url = Url::parse(url).map_err(|_| FetchError::InvalidUrl(url.to_string()))?;
+    for _ in 0..=MAX_REDIRECTS {
+        if !["http", "https"].contains(&url.scheme()) {
+            return Err(FetchError::InvalidUrl(url.to_string()));
+        }
+        let addrs = resolve_allowed(&url, is_allowed).await?;
+        let host = url.host_str().unwrap_or_default().to_string();
+        let client = reqwest::Client::builder()
+            .redirect(Policy::none())
+            .timeout(FETCH_TIMEOUT)
+            .resolve_to_addrs(&host, &addrs)
+            .build()
+            .map_err(FetchError::Request)?;
+        let mut response = client
+            .get(url.clone())
+            .send()
+            .await
+            .map_err(FetchError::Request)?;
+
+        let status = response.status();
+        if status.is_redirection() {
+            let location = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .ok_or(FetchError::Status(status.as_u16()))?;
+            url = url
+                .join(location)
+                .map_err(|_| FetchError::InvalidUrl(location.to_string()))?;
+            continue;
+        }
+        if !status.is_success() {
+            return Err(FetchError::Status(status.as_u16()));
+        }
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("")
+            .to_string();
+        let mime = content_type.split(';').next().unwrap_or("").trim();
+        if !mime.eq_ignore_ascii_case("text/html") {
+            return Err(FetchError::NotHtml(content_type));
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(FetchError::Request)? {
+            let remaining = MAX_BODY_BYTES - body.len();
+            body.extend_from_slice(&chunk[..chunk.len().min(remaining)]);
+            if body.len() >= MAX_BODY_BYTES {
+                break;
+            }
+        }
+        return Ok(String::from_utf8_lossy(&body).into_owned());
+    }
+    Err(FetchError::TooManyRedirects)
+}
+
+// 페이지의 <head> 내용 (정제 전 원본)
+pub async fn extract_head_html(url: &str) -> Result<String, FetchError> {
+    let html = fetch_html(url).await?;
+    let document = Html::parse_document(&html);
+    let selector = Selector::parse("head").unwrap();
+    Ok(document
+        .select(&selector)
+        .next()
+        .map(|head| head.html())
+        .unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks_internal_addresses() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.0.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fe80::1",
+            "fd00::1",
+            "::ffff:127.0.0.1",
+            // NAT64 (169.254.169.254), 6to4 (127.0.0.1), IPv4-compatible (10.0.0.1)
+            "64:ff9b::a9fe:a9fe",
+            "2002:7f00:0001::",
+            "::10.0.0.1",
+            "64:ff9b:1::808:808",
+            "2001:0:4136:e378::1",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in [
+            "93.184.216.34",
+            "1.1.1.1",
+            "2606:4700:4700::1111",
+            "64:ff9b::808:808",
+            "2002:808:808::",
+        ] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    // 테스트용 로컬 HTTP 서버 주소
+    async fn serve(app: axum::Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    fn allow_all(_: IpAddr) -> bool {
+        true
+    }
+
+    // /redirect/{n} 은 n 번 리다이렉트한 뒤 HTML 페이지로 응답
+    fn redirect_app() -> axum::Router {
+        use axum::{extract::Path, response::Redirect, routing::get};
+        axum::Router::new()
+            .route(
+                "/redirect/:n",
+                get(|Path(n): Path<u32>| async move {
+                    Redirect::temporary(&format!("/redirect/{}", n - 1))
+                }),
+            )
+            .route(
+                "/redirect/0",
+                get(|| async { axum::response::Html("<head></head>") }),
+            )
+    }
+
+    #[tokio::test]
+    async fn refuses_local_servers_by_default() {
+        let base = serve(redirect_app()).await;
+        assert!(matches!(
+            fetch_html(&format!("{}/redirect/0", base)).await,
+            Err(FetchError::BlockedAddress(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn follows_at_most_three_redirects() {
+        let base = serve(redirect_app()).await;
+        let html = fetch_html_with(&format!("{}/redirect/3", base), allow_all).await;
+        assert_eq!(html.unwrap(), "<head></head>");
+        assert!(matches!(
+            fetch_html_with(&format!("{}/redirect/4", base), allow_all).await,
+            Err(FetchError::TooManyRedirects)
+        ));
+    }
+
+    #[tokio::test]
+    async fn body_is_capped_at_512_kb() {
+        use axum::routing::get;
+        let app = axum::Router::new().route(
+            "/",
+            get(|| async { axum::response::Html("a".repeat(2 * MAX_BODY_BYTES)) }),
+        );
+        let base = serve(app).await;
+        let html = fetch_html_with(&base, allow_all).await.unwrap();
+        assert_eq!(html.len(), MAX_BODY_BYTES);
+    }
+
+    #[tokio::test]
+    async fn only_html_responses_are_read() {
+        use axum::routing::get;
+        let app = axum::Router::new().route(
+            "/",
+            get(|| async { axum::Json(serde_json::json!({"title": "not html"})) }),
+        );
+        let base = serve(app).await;
+        assert!(matches!(
+            fetch_html_with(&base, allow_all).await,
+            Err(FetchError::NotHtml(content_type)) if content_type == "application/json"
+        ));
+    }
+}
@@ -0,0 +1,198 @@
+use crate::utils::converter::make_short_key;
+use crate::utils::generator::generate_random_string;
+use std::env;
+
+const ALPHABET: &[u8; 62] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+// 새 URL 의 단축 키 인코딩 방식
+// 발급한 키는 DB 에 저장되므로 방식을 바꿔도 기존 링크는 그대로 접속됨
+pub trait KeyCodec: Send + Sync {
+    // 이미 사용 중인 키면 다시 호출되므로, 무작위 방식은 호출마다 다른 키를 반환
+    fn encode(&self, id: i64, random_key: &str) -> String;
+    // 같은 입력에 항상 같은 키를 반환하는지 (다시 호출해도 충돌이 풀리지 않음)
+    fn is_deterministic(&self) -> bool;
+}
+
+// 기존 방식: random_key 앞 2자 + base62 id + random_key 뒤 2자
+pub struct PaddedCodec;
+
+impl KeyCodec for PaddedCodec {
+    fn encode(&self, id: i64, random_key: &str) -> String {
+        make_short_key(id, random_key)
+    }
+
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+}
+
+// Sqids 방식: 비밀 값으로 섞은 알파벳으로 id 를 인코딩해 순서를 짐작할 수 없게 함
+pub struct SqidsCodec {
+    alphabet: Vec<u8>,
+    min_length: usize,
+}
+
+impl SqidsCodec {
+    pub fn new(secret: &str, min_length: usize) -> Self {
+        let mut alphabet = ALPHABET.to_vec();
+        secret_shuffle(&mut alphabet, secret.as_bytes());
+        shuffle(&mut alphabet);
+        SqidsCodec {
+            alphabet,
+            min_length,
+        }
+    }
+
+    fn encode_id(&self, id: u64) -> String {
+        let len = self.alphabet.len();
+        let offset = (self.alphabet[(id % len as u64) as usize] as usize + 1) % len;
+        let mut alphabet = self.alphabet.clone();
+        alphabet.rotate_left(offset);
+        let prefix = alphabet[0];
+        alphabet.reverse();
+
+        let mut key = vec![prefix];
+        key.extend(to_digits(id, &alphabet[1..]));
+        if key.len() < self.min_length {
+            key.push(alphabet[0]);
+            while key.len() < self.min_length {
+                shuffle(&mut alphabet);
+                let take = (self.min_length - key.len()).min(len);
+                key.extend_from_slice(&alphabet[..take]);
+            }
+        }
+        // 알파벳은 모두 ASCII
+        String::from_utf8(key).unwrap_or_default()
+    }
+}
+
+impl KeyCodec for SqidsCodec {
+    fn encode(&self, id: i64, _random_key: &str) -> String {
+        // DB 에서 발급한 id 는 항상 양수
+        self.encode_id(id as u64)
+    }
+
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+}
+
+// 고정 길이 무작위 키 (충돌하면 다시 생성)
+pub struct RandomCodec {
+    length: usize,
+}
+
+impl KeyCodec for RandomCodec {
+    fn encode(&self, _id: i64, _random_key: &str) -> String {
+        generate_random_string(self.length)
+    }
+
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+}
+
+// SHORT_KEY_CODEC 환경 변수로 배포마다 방식 선택 (padded, sqids, random)
+pub fn key_codec_from_env() -> Box<dyn KeyCodec> {
+    let codec = env::var("SHORT_KEY_CODEC").unwrap_or("padded".to_string());
+    let length = env::var("SHORT_KEY_LENGTH")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|&v: &usize| (4..=32).contains(&v));
+    match codec.as_str() {
+        "sqids" => {
+            // 지정하지 않으면 재시작할 때마다 알파벳이 바뀜 (이미 발급한 키는 DB 에 남아 있어 영향 없음)
+            let secret =
+                env::var("SHORT_KEY_SECRET").unwrap_or_else(|_| generate_random_string(32));
+            Box::new(SqidsCodec::new(&secret, length.unwrap_or(6)))
+        }
+        "random" => Box::new(RandomCodec {
+            length: length.unwrap_or(8),
+        }),
+        "padded" => Box::new(PaddedCodec),
+        other => {
+            println!(
+                "알 수 없는 SHORT_KEY_CODEC 값입니다: {}, padded 방식을 사용합니다",
+                other
+            );
+            Box::new(PaddedCodec)
+        }
+    }
+}
+
+// Hashids 의 salt 섞기: 비밀 값에 따라 결정적으로 알파벳 순서를 바꿈
+fn secret_shuffle(alphabet: &mut [u8], secret: &[u8]) {
+    if secret.is_empty() {
+        return;
+    }
+    let mut v = 0;
+    let mut p = 0;
+    for i in (1..alphabet.len()).rev() {
+        v %= secret.len();
+        let n = secret[v] as usize;
+        p += n;
+        let j = (n + v + p) % i;
+        alphabet.swap(i, j);
+        v += 1;
+    }
+}
+
+// Sqids 의 알파벳 섞기
+fn shuffle(alphabet: &mut [u8]) {
+    let len = alphabet.len();
+    let mut i = 0;
+    let mut j = len - 1;
+    while j > 0 {
+        let r = (i * j + alphabet[i] as usize + alphabet[j] as usize) % len;
+        alphabet.swap(i, r);
+        i += 1;
+        j -= 1;
+    }
+}
+
+fn to_digits(mut id: u64, alphabet: &[u8]) -> Vec<u8> {
+    let base = alphabet.len() as u64;
+    let mut digits = Vec::new();
+    loop {
+        digits.push(alphabet[(id % base) as usize]);
+        id /= base;
+        if id == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+    digits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn sqids_keys_are_unique_and_padded() {
+        let codec = SqidsCodec::new("secret", 6);
+        let keys: HashSet<String> = (1..10_000).map(|id| codec.encode(id, "")).collect();
+        assert_eq!(keys.len(), 9_999);
+        assert!(keys
+            .iter()
+            .all(|k| k.len() >= 6 && k.bytes().all(|b| b.is_ascii_alphanumeric())));
+    }
+
+    #[test]
+    fn sqids_alphabet_depends_on_secret() {
+        let a = SqidsCodec::new("secret-a", 6);
+        let b = SqidsCodec::new("secret-b", 6);
+        assert_eq!(a.encode(42, ""), a.encode(42, ""));
+        assert_ne!(a.encode(42, ""), b.encode(42, ""));
+    }
+
+    #[test]
+    fn only_random_keys_change_on_retry() {
+        let random = RandomCodec { length: 8 };
+        assert!(!random.is_deterministic());
+        assert_ne!(random.encode(42, "abcd"), random.encode(42, "abcd"));
+        assert!(PaddedCodec.is_deterministic());
+        assert!(SqidsCodec::new("secret", 6).is_deterministic());
+    }
+}
@@ -1,2 +1,16 @@
+pub mod android_intent;
+pub mod app_links;
+pub mod client_ip;
 pub mod converter;
+pub mod fetcher;
 pub mod generator;
+pub mod key_codec;
+pub mod open_graph;
+pub mod password;
+pub mod platform;
+pub mod sanitizer;
+pub mod short_url;
+pub mod signature;
+pub mod template;
+pub mod url_safety;
+pub mod webhook_policy;
@@ -0,0 +1,85 @@
+use crate::utils::sanitizer::sanitize_head_html_excluding;
+use crate::utils::template::escape_html;
+
+// 링크 미리보기용 구조화된 메타데이터 (빈 값은 지정하지 않음)
+pub struct OpenGraph<'a> {
+    pub title: &'a str,
+    pub description: &'a str,
+    pub image: &'a str,
+    pub twitter_card: &'a str,
+}
+
+impl OpenGraph<'_> {
+    // 미리보기 페이지의 head 내용
+    // 지정한 값으로 meta 태그를 만들고, 원본 head_html 에서는 같은 항목을 지워 중복되지 않게 함
+    pub fn head_html(&self, short_url: &str, raw_head_html: &str) -> String {
+        let mut tags = Vec::new();
+        let mut excluded = Vec::new();
+        // og:* 는 property, 나머지는 name 속성 사용
+        let mut push = |keys: &[&'static str], value: &str| {
+            if value.is_empty() {
+                return;
+            }
+            for key in keys {
+                tags.push(format!(
+                    "<meta {}=\"{}\" content=\"{}\">",
+                    if key.starts_with("og:") {
+                        "property"
+                    } else {
+                        "name"
+                    },
+                    key,
+                    escape_html(value)
+                ));
+                excluded.push(*key);
+            }
+        };
+        push(&["og:title", "twitter:title"], self.title);
+        push(
+            &["og:description", "twitter:description", "description"],
+            self.description,
+        );
+        push(&["og:image", "twitter:image"], self.image);
+        push(&["twitter:card"], self.twitter_card);
+        push(&["og:url"], short_url);
+
+        if !self.title.is_empty() {
+            tags.insert(0, format!("<title>{}</title>", escape_html(self.title)));
+            excluded.push("title");
+        }
+        let rest = sanitize_head_html_excluding(raw_head_html, &excluded);
+        if !rest.is_empty() {
+            tags.push(rest);
+        }
+        tags.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn structured_fields_replace_raw_tags() {
+        let open_graph = OpenGraph {
+            title: "Sale <50%>",
+            description: "",
+            image: "",
+            twitter_card: "summary",
+        };
+        let head_html = open_graph.head_html(
+            "https://s.example/abc",
+            "<title>Old</title><meta property=\"og:title\" content=\"Old\">\
+             <meta name=\"description\" content=\"Kept\">",
+        );
+        assert_eq!(
+            head_html,
+            "<title>Sale &lt;50%&gt;</title>\n\
+             <meta property=\"og:title\" content=\"Sale &lt;50%&gt;\">\n\
+             <meta name=\"twitter:title\" content=\"Sale &lt;50%&gt;\">\n\
+             <meta name=\"twitter:card\" content=\"summary\">\n\
+             <meta property=\"og:url\" content=\"https://s.example/abc\">\n\
+             <meta name=\"description\" content=\"Kept\">"
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use crate::utils::signature::{constant_time_eq, hmac_sha256, to_hex};
+use argon2::password_hash::{rand_core::OsRng, PasswordHash, SaltString};
+use argon2::{Argon2, PasswordHasher, PasswordVerifier};
+use axum::http::{header::COOKIE, HeaderMap};
+
+// 링크 비밀번호 해시 (Argon2id, CPU 를 오래 쓰므로 spawn_blocking 안에서 호출)
+pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default()
+        .hash_password(password.as_bytes(), &salt)?
+        .to_string())
+}
+
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    PasswordHash::new(password_hash)
+        .map(|hash| {
+            Argon2::default()
+                .verify_password(password.as_bytes(), &hash)
+                .is_ok()
+        })
+        .unwrap_or(false)
+}
+
+pub fn unlock_cookie_name(url_id: i64) -> String {
+    format!("unlock_{}", url_id)
+}
+
+// 잠금 해제 쿠키 값: "{만료 시각}.{서명}"
+// 비밀번호 해시를 서명에 포함해 비밀번호가 바뀌면 기존 쿠키는 무효가 됨
+pub fn sign_unlock_token(secret: &str, url_id: i64, password_hash: &str, expires: i64) -> String {
+    let message = format!("{}.{}.{}", url_id, expires, password_hash);
+    let digest = hmac_sha256(secret.as_bytes(), message.as_bytes());
+    format!("{}.{}", expires, to_hex(&digest))
+}
+
+// 요청에 만료되지 않은 올바른 잠금 해제 쿠키가 있는지 확인
+pub fn has_unlock_cookie(
+    headers: &HeaderMap,
+    secret: &str,
+    url_id: i64,
+    password_hash: &str,
+    now: i64,
+) -> bool {
+    let name = unlock_cookie_name(url_id);
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .filter(|(key, _)| *key == name)
+        .any(|(_, token)| {
+            let Some(expires) = token
+                .split_once('.')
+                .and_then(|(expires, _)| expires.parse::<i64>().ok())
+            else {
+                return false;
+            };
+            let expected = sign_unlock_token(secret, url_id, password_hash, expires);
+            expires > now && constant_time_eq(expected.as_bytes(), token.as_bytes())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "cookie-secret";
+    const HASH: &str = "$argon2id$v=19$m=19456,t=2,p=1$c2FsdA$aGFzaA";
+    const NOW: i64 = 1_700_000_000;
+
+    // cookie_id 링크의 쿠키로 token 을 보냈을 때 url_id 링크가 열리는지
+    fn unlocks(cookie_id: i64, token: &str, url_id: i64, password_hash: &str, now: i64) -> bool {
+        let mut headers = HeaderMap::new();
+        let value = format!("other=1; {}={}", unlock_cookie_name(cookie_id), token);
+        headers.insert(COOKIE, value.parse().unwrap());
+        has_unlock_cookie(&headers, SECRET, url_id, password_hash, now)
+    }
+
+    #[test]
+    fn valid_cookie_unlocks() {
+        let token = sign_unlock_token(SECRET, 7, HASH, NOW + 60);
+        assert!(token.starts_with(&format!("{}.", NOW + 60)));
+        assert!(unlocks(7, &token, 7, HASH, NOW));
+        assert!(!has_unlock_cookie(&HeaderMap::new(), SECRET, 7, HASH, NOW));
+    }
+
+    #[test]
+    fn tampered_cookie_is_rejected() {
+        let token = sign_unlock_token(SECRET, 7, HASH, NOW + 60);
+        // 만료 시각만 늘리면 서명이 맞지 않음
+        let extended = token.replacen(&(NOW + 60).to_string(), &(NOW + 6000).to_string(), 1);
+        assert!(!unlocks(7, &extended, 7, HASH, NOW));
+        let mut flipped = token.clone();
+        let last = if flipped.ends_with('0') { "1" } else { "0" };
+        flipped.replace_range(flipped.len() - 1.., last);
+        assert!(!unlocks(7, &flipped, 7, HASH, NOW));
+        assert!(!unlocks(7, "garbage", 7, HASH, NOW));
+        // 다른 서명 키로 만든 쿠키
+        let forged = sign_unlock_token("other-secret", 7, HASH, NOW + 60);
+        assert!(!unlocks(7, &forged, 7, HASH, NOW));
+    }
+
+    #[test]
+    fn expired_cookie_is_rejected() {
+        let token = sign_unlock_token(SECRET, 7, HASH, NOW);
+        assert!(!unlocks(7, &token, 7, HASH, NOW));
+        assert!(unlocks(7, &token, 7, HASH, NOW - 1));
+    }
+
+    #[test]
+    fn cookie_is_bound_to_link_and_password() {
+        let token = sign_unlock_token(SECRET, 7, HASH, NOW + 60);
+        // 다른 링크의 쿠키 이름으로 보내거나, 이름만 바꿔 다른 링크에 써도 거부
+        assert!(!unlocks(7, &token, 8, HASH, NOW));
+        assert!(!unlocks(8, &token, 8, HASH, NOW));
+        // 비밀번호가 바뀌면 기존 쿠키는 무효
+        let changed = "$argon2id$v=19$m=19456,t=2,p=1$c2FsdA$b3RoZXI";
+        assert!(!unlocks(7, &token, 7, changed, NOW));
+    }
+}
@@ -0,0 +1,114 @@
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Platform {
+    Ios,
+    Android,
+    Other,
+}
+
+impl Platform {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Platform::Ios => "ios",
+            Platform::Android => "android",
+            Platform::Other => "other",
+        }
+    }
+}
+
+// User-Agent 로 플랫폼 판별 (Android 를 먼저 확인)
+pub fn detect_platform(user_agent: &str) -> Platform {
+    let user_agent = user_agent.to_lowercase();
+    if user_agent.contains("android") {
+        Platform::Android
+    } else if ["iphone", "ipad", "ipod"]
+        .iter()
+        .any(|device| user_agent.contains(device))
+    {
+        Platform::Ios
+    } else {
+        Platform::Other
+    }
+}
+
+// 링크 미리보기를 만드는 크롤러 (카카오톡 인앱 브라우저와 구분하기 위해 kakaotalk-scrap 으로 확인)
+const PREVIEW_CRAWLERS: [&str; 4] = [
+    "slackbot",
+    "facebookexternalhit",
+    "twitterbot",
+    "kakaotalk-scrap",
+];
+
+pub fn is_preview_crawler(user_agent: &str) -> bool {
+    let user_agent = user_agent.to_lowercase();
+    PREVIEW_CRAWLERS
+        .iter()
+        .any(|crawler| user_agent.contains(crawler))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ios_devices_are_detected() {
+        for user_agent in [
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1",
+            "Mozilla/5.0 (iPad; CPU OS 16_6 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) CriOS/117.0.5938.108 Mobile/15E148 Safari/604.1",
+            "Mozilla/5.0 (iPod touch; CPU iPhone OS 15_7 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Mobile/15E148 KAKAOTALK 10.3.5",
+        ] {
+            assert_eq!(detect_platform(user_agent), Platform::Ios, "{}", user_agent);
+        }
+    }
+
+    // iPadOS 13 이상의 데스크톱 모드 Safari 는 macOS 와 같은 User-Agent 를 보내므로 서버에서는 구분할 수 없음
+    #[test]
+    fn ipados_desktop_mode_is_treated_as_other() {
+        let user_agent = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Safari/605.1.15";
+        assert_eq!(detect_platform(user_agent), Platform::Other);
+    }
+
+    #[test]
+    fn android_is_detected_before_ios_keywords() {
+        for user_agent in [
+            "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/119.0.0.0 Mobile Safari/537.36",
+            "Mozilla/5.0 (Linux; Android 13; SM-X910) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/118.0.0.0 Safari/537.36",
+            // Android 태블릿 중 iPad 를 흉내 내는 User-Agent
+            "Mozilla/5.0 (Linux; Android 12; like iPad) AppleWebKit/537.36",
+        ] {
+            assert_eq!(
+                detect_platform(user_agent),
+                Platform::Android,
+                "{}",
+                user_agent
+            );
+        }
+    }
+
+    #[test]
+    fn other_clients_fall_back_to_other() {
+        for user_agent in [
+            "",
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/119.0.0.0 Safari/537.36",
+            "Mozilla/5.0 (X11; Linux x86_64; rv:109.0) Gecko/20100101 Firefox/119.0",
+            "curl/8.4.0",
+        ] {
+            assert_eq!(detect_platform(user_agent), Platform::Other, "{}", user_agent);
+        }
+    }
+
+    #[test]
+    fn preview_crawlers_are_detected() {
+        assert!(is_preview_crawler(
+            "Slackbot-LinkExpanding 1.0 (+https://api.slack.com/robots)"
+        ));
+        assert!(is_preview_crawler(
+            "facebookexternalhit/1.1 (+http://www.facebook.com/externalhit_uatext.php)"
+        ));
+        assert!(!is_preview_crawler(
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) KAKAOTALK 10.3.5"
+        ));
+    }
+}
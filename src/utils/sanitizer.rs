@@ -0,0 +1,127 @@
+use crate::utils::template::escape_html;
+use scraper::{Html, Selector};
+
+const META_ATTRIBUTES: &[&str] = &["name", "property", "content", "charset", "itemprop"];
+const LINK_ATTRIBUTES: &[&str] = &["rel", "href", "type", "sizes", "hreflang"];
+const LINK_RELS: &[&str] = &[
+    "icon",
+    "shortcut icon",
+    "apple-touch-icon",
+    "canonical",
+    "alternate",
+    "image_src",
+];
+
+// head_html 정제
+// meta, title, link 태그만 남기고 허용된 속성만 이스케이프해 다시 생성
+// (http-equiv 로 인한 새로고침, 스크립트, 스타일, 이벤트 핸들러 속성은 모두 제거)
+pub fn sanitize_head_html(head_html: &str) -> String {
+    sanitize_head_html_excluding(head_html, &[])
+}
+
+// 정제하면서 excluded 에 있는 태그도 제거
+// meta 는 name/property 값, title 태그는 "title" 로 지정 (구조화된 값으로 대체할 때 사용)
+pub fn sanitize_head_html_excluding(head_html: &str, excluded: &[&str]) -> String {
+    if head_html.trim().is_empty() {
+        return String::new();
+    }
+    let fragment = Html::parse_fragment(head_html);
+    let selector = Selector::parse("meta, title, link").unwrap();
+
+    let mut sanitized = Vec::new();
+    for element in fragment.select(&selector) {
+        let tag = element.value().name();
+        match tag {
+            "title" => {
+                if excluded.contains(&"title") {
+                    continue;
+                }
+                let text: String = element.text().collect();
+                sanitized.push(format!("<title>{}</title>", escape_html(text.trim())));
+            }
+            "meta" | "link" => {
+                let allowed = if tag == "meta" {
+                    META_ATTRIBUTES
+                } else {
+                    LINK_ATTRIBUTES
+                };
+                if tag == "meta"
+                    && !["name", "property", "charset", "itemprop"]
+                        .iter()
+                        .any(|name| element.value().attr(name).is_some())
+                {
+                    continue;
+                }
+                if tag == "meta"
+                    && ["name", "property"].iter().any(|name| {
+                        element.value().attr(name).is_some_and(|key| {
+                            excluded.contains(&key.trim().to_lowercase().as_str())
+                        })
+                    })
+                {
+                    continue;
+                }
+                if tag == "link" {
+                    let rel = element.value().attr("rel").unwrap_or("").to_lowercase();
+                    let href = element.value().attr("href").unwrap_or("");
+                    if !LINK_RELS.contains(&rel.trim()) || !is_http_url(href) {
+                        continue;
+                    }
+                }
+                let attributes: String = allowed
+                    .iter()
+                    .filter_map(|name| {
+                        element
+                            .value()
+                            .attr(name)
+                            .map(|value| format!(" {}=\"{}\"", name, escape_html(value)))
+                    })
+                    .collect();
+                if !attributes.is_empty() {
+                    sanitized.push(format!("<{}{}>", tag, attributes));
+                }
+            }
+            _ => {}
+        }
+    }
+    sanitized.join("\n")
+}
+
+fn is_http_url(url: &str) -> bool {
+    let url = url.trim().to_lowercase();
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_scripts_refresh_and_event_handlers() {
+        let head_html = r#"
+            <script>alert(1)</script>
+            <meta http-equiv="refresh" content="0;url=javascript:alert(1)">
+            <meta name="description" content="ok" onerror="alert(1)">
+            <img src=x onerror=alert(1)>
+            <link rel="stylesheet" href="https://evil.example.com/a.css">
+            <link rel="icon" href="javascript:alert(1)">
+            <title>a</title><script>alert(2)</script>
+        "#;
+        let sanitized = sanitize_head_html(head_html);
+        assert_eq!(
+            sanitized,
+            "<meta name=\"description\" content=\"ok\">\n<title>a</title>"
+        );
+    }
+
+    #[test]
+    fn escapes_attribute_values() {
+        let sanitized = sanitize_head_html(
+            r#"<meta property="og:title" content="&quot;><script>x</script>'">"#,
+        );
+        assert_eq!(
+            sanitized,
+            "<meta property=\"og:title\" content=\"&quot;&gt;&lt;script&gt;x&lt;/script&gt;&#x27;\">"
+        );
+    }
+}
@@ -0,0 +1,20 @@
+use std::env;
+
+// 서버 외부 접근 주소
+pub fn base_url() -> String {
+    let host = env::var("SERVER_HOST").unwrap_or("127.0.0.1".to_string());
+    let port = env::var("SERVER_PORT").unwrap_or("3000".to_string());
+    format!("http://{}:{}", host, port)
+}
+
+pub fn short_url(short_key: &str) -> String {
+    format!("{}/{}", base_url(), short_key)
+}
+
+// 앱이 설치되어 있으면 앱으로 바로 열리는 주소 (딥링크가 있는 링크만)
+pub fn app_url(short_key: &str, ios_deep_link: &str, android_deep_link: &str) -> Option<String> {
+    if ios_deep_link.is_empty() && android_deep_link.is_empty() {
+        return None;
+    }
+    Some(format!("{}/app/{}", base_url(), short_key))
+}
@@ -0,0 +1,193 @@
+use sha2::{Digest, Sha256};
+
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+
+const BLOCK_SIZE: usize = 64;
+
+// HMAC-SHA256 (RFC 2104)
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let inner_pad: Vec<u8> = block.iter().map(|b| b ^ 0x36).collect();
+    let outer_pad: Vec<u8> = block.iter().map(|b| b ^ 0x5c).collect();
+    let inner = Sha256::new()
+        .chain_update(&inner_pad)
+        .chain_update(message)
+        .finalize();
+    Sha256::new()
+        .chain_update(&outer_pad)
+        .chain_update(inner)
+        .finalize()
+        .into()
+}
+
+// 웹훅 서명 생성: "sha256=" + hex(HMAC-SHA256(secret, "{timestamp}.{body}"))
+pub fn sign_webhook(secret: &str, timestamp: i64, body: &str) -> String {
+    let message = format!("{}.{}", timestamp, body);
+    let digest = hmac_sha256(secret.as_bytes(), message.as_bytes());
+    format!("sha256={}", to_hex(&digest))
+}
+
+// 소문자 hex 문자열로 변환
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// 상수 시간 비교 (서명처럼 어디까지 일치하는지가 응답 시간으로 드러나면 안 되는 값)
+// 길이는 비밀이 아니므로 다르면 바로 false
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // README 의 수신 측 검증 절차 (서버는 서명만 하므로 테스트에서만 사용)
+    // 타임스탬프가 now 기준 tolerance_seconds 를 벗어나면 재전송 공격으로 보고 거부
+    fn verify_webhook_signature(
+        secret: &str,
+        timestamp: i64,
+        body: &str,
+        signature: &str,
+        now: i64,
+        tolerance_seconds: i64,
+    ) -> bool {
+        if (now - timestamp).abs() > tolerance_seconds {
+            return false;
+        }
+        let expected = sign_webhook(secret, timestamp, body);
+        constant_time_eq(expected.as_bytes(), signature.as_bytes())
+    }
+
+    // RFC 4231 테스트 벡터
+    #[test]
+    fn hmac_matches_rfc4231() {
+        let cases: [(Vec<u8>, Vec<u8>, &str); 5] = [
+            (
+                vec![0x0b; 20],
+                b"Hi There".to_vec(),
+                "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+            ),
+            (
+                b"Jefe".to_vec(),
+                b"what do ya want for nothing?".to_vec(),
+                "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            ),
+            (
+                vec![0xaa; 20],
+                vec![0xdd; 50],
+                "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe",
+            ),
+            // 블록 크기보다 긴 키는 해시한 뒤 사용
+            (
+                vec![0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First".to_vec(),
+                "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+            ),
+            (
+                vec![0xaa; 131],
+                b"This is a test using a larger than block-size key and a larger than block-size data. The key needs to be hashed before being used by the HMAC algorithm.".to_vec(),
+                "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
+            ),
+        ];
+        for (key, message, expected) in cases {
+            assert_eq!(to_hex(&hmac_sha256(&key, &message)), expected);
+        }
+    }
+
+    #[test]
+    fn signature_round_trip() {
+        let body = r#"{"short_key":"adbT7"}"#;
+        let signature = sign_webhook("whsec_test", 1_700_000_000, body);
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert!(verify_webhook_signature(
+            "whsec_test",
+            1_700_000_000,
+            body,
+            &signature,
+            1_700_000_010,
+            300
+        ));
+    }
+
+    #[test]
+    fn tampered_requests_are_rejected() {
+        let body = r#"{"short_key":"adbT7"}"#;
+        let signature = sign_webhook("whsec_test", 1_700_000_000, body);
+        let now = 1_700_000_000;
+        // 본문, 타임스탬프, 비밀 키, 서명이 하나라도 다르면 거부
+        assert!(!verify_webhook_signature(
+            "whsec_test",
+            now,
+            r#"{"short_key":"adbT8"}"#,
+            &signature,
+            now,
+            300
+        ));
+        assert!(!verify_webhook_signature(
+            "whsec_test",
+            now + 1,
+            body,
+            &signature,
+            now,
+            300
+        ));
+        assert!(!verify_webhook_signature(
+            "whsec_other",
+            now,
+            body,
+            &signature,
+            now,
+            300
+        ));
+        assert!(!verify_webhook_signature(
+            "whsec_test",
+            now,
+            body,
+            &signature[..signature.len() - 1],
+            now,
+            300
+        ));
+        assert!(!verify_webhook_signature(
+            "whsec_test",
+            now,
+            body,
+            "",
+            now,
+            300
+        ));
+    }
+
+    #[test]
+    fn replayed_requests_are_rejected() {
+        let body = r#"{"short_key":"adbT7"}"#;
+        let signature = sign_webhook("whsec_test", 1_700_000_000, body);
+        for now in [1_700_000_000 + 300, 1_700_000_000 - 300] {
+            assert!(verify_webhook_signature(
+                "whsec_test",
+                1_700_000_000,
+                body,
+                &signature,
+                now,
+                300
+            ));
+        }
+        for now in [1_700_000_000 + 301, 1_700_000_000 - 301] {
+            assert!(!verify_webhook_signature(
+                "whsec_test",
+                1_700_000_000,
+                body,
+                &signature,
+                now,
+                300
+            ));
+        }
+    }
+}
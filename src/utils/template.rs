@@ -0,0 +1,123 @@
+// 템플릿 렌더링
+// 자리표시자는 {컨텍스트:이름} 형태이며 값은 컨텍스트에 맞게 이스케이프됨
+//   {text:이름}  HTML 본문
+//   {attr:이름}  따옴표로 감싼 HTML 속성값
+//   {js:이름}    JavaScript 문자열 리터럴 내부
+//   {html:이름}  이미 정제된 HTML (이스케이프하지 않음)
+pub fn render(template: &str, values: &[(&str, &str)]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let placeholder = rest
+            .find('}')
+            .map(|end| &rest[1..end])
+            .and_then(|inner| inner.split_once(':'))
+            .filter(|(_, name)| {
+                !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            });
+        let escaped = placeholder.and_then(|(context, name)| {
+            let value = values
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| *value)
+                .unwrap_or("");
+            match context {
+                "text" | "attr" => Some(escape_html(value)),
+                "js" => Some(escape_js_string(value)),
+                "html" => Some(value.to_string()),
+                _ => None,
+            }
+        });
+        match (placeholder, escaped) {
+            (Some((context, name)), Some(escaped)) => {
+                output.push_str(&escaped);
+                rest = &rest[context.len() + name.len() + 3..];
+            }
+            _ => {
+                // 자리표시자가 아닌 중괄호 (JS, CSS 블록 등)
+                output.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+// HTML 본문과 따옴표로 감싼 속성값 모두에 안전한 이스케이프
+pub fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// JavaScript 문자열 리터럴 이스케이프
+// 따옴표와 역슬래시 외에 <, >, & 도 유니코드 이스케이프해 </script> 로 스크립트 블록을 벗어날 수 없도록 함
+pub fn escape_js_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\'' => escaped.push_str("\\'"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '<' | '>' | '&' | '`' | '\u{2028}' | '\u{2029}' => {
+                escaped.push_str(&format!("\\u{:04X}", c as u32))
+            }
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04X}", c as u32)),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn js_strings_cannot_break_out_of_the_script() {
+        assert_eq!(
+            escape_js_string("';alert(1)//</script>"),
+            "\\';alert(1)//\\u003C/script\\u003E"
+        );
+        assert_eq!(escape_js_string("a\\'b\n\u{2028}"), "a\\\\\\'b\\n\\u2028");
+    }
+
+    #[test]
+    fn render_escapes_by_context() {
+        let template =
+            "<p>{text:v}</p><a href=\"{attr:v}\"></a><script>var v = '{js:v}';</script>{html:h}";
+        let value = "\" onerror='x'</script>";
+        let output = render(template, &[("v", value), ("h", "<meta name=\"a\">")]);
+        assert_eq!(
+            output,
+            "<p>&quot; onerror=&#x27;x&#x27;&lt;/script&gt;</p>\
+             <a href=\"&quot; onerror=&#x27;x&#x27;&lt;/script&gt;\"></a>\
+             <script>var v = '\\\" onerror=\\'x\\'\\u003C/script\\u003E';</script>\
+             <meta name=\"a\">"
+        );
+    }
+
+    #[test]
+    fn render_keeps_braces_that_are_not_placeholders() {
+        let output = render("function f() { return {a: 1}; } {text:missing}", &[]);
+        assert_eq!(output, "function f() { return {a: 1}; } ");
+    }
+}
@@ -0,0 +1,364 @@
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+// 차단 목록 파일이 바뀌었는지 확인하는 간격
+const RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+const HOOK_TIMEOUT: Duration = Duration::from_secs(3);
+
+// 목적지 URL 이 거부된 이유
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnsafeReason {
+    // 차단 목록의 도메인 (하위 도메인 포함)
+    BlockedDomain(String),
+    // 차단 목록의 정규식
+    BlockedPattern(String),
+    // 외부 평판 서비스가 위험하다고 판단 (서비스 이름, 사유)
+    Reputation(String, String),
+    // 외부 평판 서비스에 확인할 수 없고 실패 시 거부하도록 설정됨
+    Unavailable(String),
+}
+
+impl UnsafeReason {
+    // API 응답의 reason 값
+    pub fn code(&self) -> &'static str {
+        match self {
+            UnsafeReason::BlockedDomain(_) => "blocked_domain",
+            UnsafeReason::BlockedPattern(_) => "blocked_pattern",
+            UnsafeReason::Reputation(_, _) => "reputation",
+            UnsafeReason::Unavailable(_) => "check_unavailable",
+        }
+    }
+}
+
+impl fmt::Display for UnsafeReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnsafeReason::BlockedDomain(domain) => write!(f, "차단된 도메인입니다: {}", domain),
+            UnsafeReason::BlockedPattern(pattern) => {
+                write!(f, "차단된 URL 패턴과 일치합니다: {}", pattern)
+            }
+            UnsafeReason::Reputation(provider, reason) => {
+                write!(
+                    f,
+                    "{} 에서 위험한 URL 로 판단했습니다: {}",
+                    provider, reason
+                )
+            }
+            UnsafeReason::Unavailable(provider) => {
+                write!(f, "{} 에서 URL 안전성을 확인할 수 없습니다", provider)
+            }
+        }
+    }
+}
+
+// 거부된 필드와 URL
+#[derive(Debug)]
+pub struct UnsafeUrl {
+    // API 요청의 필드 이름 (예: defaultFallbackUrl)
+    pub field: &'static str,
+    pub url: String,
+    pub reason: UnsafeReason,
+}
+
+impl fmt::Display for UnsafeUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.reason)
+    }
+}
+
+impl std::error::Error for UnsafeUrl {}
+
+// 목적지 URL 검사 (차단 목록, 외부 평판 서비스 등)
+// 새 서비스를 붙일 때는 이 트레이트를 구현해 UrlSafety::new 에 추가
+#[async_trait]
+pub trait UrlSafetyCheck: Send + Sync {
+    async fn check(&self, url: &str) -> Result<(), UnsafeReason>;
+}
+
+// 등록된 검사를 순서대로 실행하고 처음 거부된 이유를 반환
+pub struct UrlSafety {
+    checks: Vec<Box<dyn UrlSafetyCheck>>,
+}
+
+impl UrlSafety {
+    pub fn new(checks: Vec<Box<dyn UrlSafetyCheck>>) -> Self {
+        UrlSafety { checks }
+    }
+
+    // URL_BLOCKLIST_PATH, URL_SAFETY_HOOK_URL 이 지정된 검사만 사용
+    pub fn from_env() -> Self {
+        let mut checks: Vec<Box<dyn UrlSafetyCheck>> = Vec::new();
+        if let Ok(path) = env::var("URL_BLOCKLIST_PATH") {
+            checks.push(Box::new(BlocklistCheck::new(path, RELOAD_INTERVAL)));
+        }
+        if let Some(hook) = ReputationHook::from_env() {
+            checks.push(Box::new(hook));
+        }
+        UrlSafety::new(checks)
+    }
+
+    // (필드 이름, URL) 목록 검사, 빈 URL 은 건너뜀
+    pub async fn check(&self, urls: &[(&'static str, &str)]) -> Result<(), UnsafeUrl> {
+        for &(field, url) in urls.iter().filter(|(_, url)| !url.is_empty()) {
+            for check in &self.checks {
+                if let Err(reason) = check.check(url).await {
+                    return Err(UnsafeUrl {
+                        field,
+                        url: url.to_string(),
+                        reason,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// 차단 목록 파일 (한 줄에 하나, '#' 으로 시작하면 주석)
+// 예: evil.example.com          -> 해당 도메인과 하위 도메인
+//     regex:^https?://[^/]+/wp-admin/ -> URL 전체에 대한 정규식
+#[derive(Default)]
+struct Blocklist {
+    domains: Vec<String>,
+    patterns: Vec<Regex>,
+}
+
+impl Blocklist {
+    fn parse(content: &str) -> Self {
+        let mut blocklist = Blocklist::default();
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.strip_prefix("regex:") {
+                Some(pattern) => match Regex::new(pattern.trim()) {
+                    Ok(regex) => blocklist.patterns.push(regex),
+                    Err(e) => println!("차단 목록의 정규식이 올바르지 않습니다: {} ({})", line, e),
+                },
+                None => blocklist
+                    .domains
+                    .push(line.trim_end_matches('.').to_lowercase()),
+            }
+        }
+        blocklist
+    }
+
+    fn find(&self, url: &str) -> Option<UnsafeReason> {
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|url| {
+                url.host_str()
+                    .map(|host| host.trim_end_matches('.').to_lowercase())
+            })
+            .unwrap_or_default();
+        if let Some(domain) = self
+            .domains
+            .iter()
+            .find(|domain| host == **domain || host.ends_with(&format!(".{}", domain)))
+        {
+            return Some(UnsafeReason::BlockedDomain(domain.clone()));
+        }
+        self.patterns
+            .iter()
+            .find(|pattern| pattern.is_match(url))
+            .map(|pattern| UnsafeReason::BlockedPattern(pattern.as_str().to_string()))
+    }
+}
+
+// 파일 차단 목록 (수정 시각이 바뀌면 재시작 없이 다시 읽음)
+// 파일은 백그라운드 작업이 다시 읽어 목록을 통째로 교체하고, 검사는 현재 목록만 참조
+pub struct BlocklistCheck {
+    current: Arc<RwLock<Arc<Blocklist>>>,
+}
+
+impl BlocklistCheck {
+    // tokio 런타임 안에서 호출 (검사 객체가 사라지면 다시 읽는 작업도 종료)
+    pub fn new(path: impl Into<PathBuf>, reload_interval: Duration) -> Self {
+        let path = path.into();
+        let (blocklist, mut modified) = load_blocklist(&path, None, true).unwrap_or_default();
+        let current = Arc::new(RwLock::new(Arc::new(blocklist)));
+        let weak = Arc::downgrade(&current);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(reload_interval).await;
+                let Some(current) = weak.upgrade() else {
+                    break;
+                };
+                let path = path.clone();
+                let loaded =
+                    tokio::task::spawn_blocking(move || load_blocklist(&path, modified, false))
+                        .await;
+                if let Ok(Some((blocklist, changed))) = loaded {
+                    *current.write().unwrap() = Arc::new(blocklist);
+                    modified = changed;
+                }
+            }
+        });
+        BlocklistCheck { current }
+    }
+}
+
+// 수정 시각이 바뀐 경우에만 읽음 (동기 파일 입출력이므로 요청 처리 중에는 호출하지 않음)
+// 파일을 읽지 못하면 None 을 반환해 이전 목록을 그대로 사용
+fn load_blocklist(
+    path: &Path,
+    previous: Option<SystemTime>,
+    force: bool,
+) -> Option<(Blocklist, Option<SystemTime>)> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+    if !force && modified == previous {
+        return None;
+    }
+    match fs::read_to_string(path) {
+        Ok(content) => {
+            let blocklist = Blocklist::parse(&content);
+            println!(
+                "차단 목록을 읽었습니다: {} (도메인 {}개, 정규식 {}개)",
+                path.display(),
+                blocklist.domains.len(),
+                blocklist.patterns.len()
+            );
+            Some((blocklist, modified))
+        }
+        Err(e) => {
+            println!("차단 목록을 읽을 수 없습니다: {} ({})", path.display(), e);
+            None
+        }
+    }
+}
+
+#[async_trait]
+impl UrlSafetyCheck for BlocklistCheck {
+    async fn check(&self, url: &str) -> Result<(), UnsafeReason> {
+        let blocklist = self.current.read().unwrap().clone();
+        match blocklist.find(url) {
+            Some(reason) => Err(reason),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ReputationRequest<'a> {
+    url: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ReputationResponse {
+    safe: bool,
+    #[serde(default)]
+    reason: String,
+}
+
+// 외부 평판 서비스 연동
+// URL_SAFETY_HOOK_URL 로 {"url": ...} 를 POST 하고 {"safe": bool, "reason": "..."} 응답을 받음
+// 서비스 장애 시 기본값은 통과, URL_SAFETY_HOOK_FAIL_CLOSED=true 면 거부
+pub struct ReputationHook {
+    endpoint: String,
+    fail_closed: bool,
+    client: reqwest::Client,
+}
+
+impl ReputationHook {
+    pub fn from_env() -> Option<Self> {
+        let endpoint = env::var("URL_SAFETY_HOOK_URL").unwrap_or("".to_string());
+        if endpoint.is_empty() {
+            return None;
+        }
+        let fail_closed = env::var("URL_SAFETY_HOOK_FAIL_CLOSED")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        let client = reqwest::Client::builder()
+            .timeout(HOOK_TIMEOUT)
+            .build()
+            .ok()?;
+        Some(ReputationHook {
+            endpoint,
+            fail_closed,
+            client,
+        })
+    }
+
+    async fn lookup(&self, url: &str) -> Result<ReputationResponse, reqwest::Error> {
+        self.client
+            .post(&self.endpoint)
+            .json(&ReputationRequest { url })
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    }
+}
+
+#[async_trait]
+impl UrlSafetyCheck for ReputationHook {
+    async fn check(&self, url: &str) -> Result<(), UnsafeReason> {
+        match self.lookup(url).await {
+            Ok(response) if response.safe => Ok(()),
+            Ok(response) => Err(UnsafeReason::Reputation(
+                "reputation-hook".to_string(),
+                response.reason,
+            )),
+            Err(e) => {
+                println!("URL 평판 서비스 호출 실패: {}", e);
+                if self.fail_closed {
+                    Err(UnsafeReason::Unavailable("reputation-hook".to_string()))
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocklist_matches_domains_and_patterns() {
+        let blocklist = Blocklist::parse(
+            "# 주석\nEvil.example.com\n\nregex:^https?://[^/]+/wp-admin/\nregex:([\n",
+        );
+        assert_eq!(blocklist.patterns.len(), 1);
+        assert_eq!(
+            blocklist.find("https://login.evil.example.com/a"),
+            Some(UnsafeReason::BlockedDomain("evil.example.com".to_string()))
+        );
+        assert!(blocklist.find("https://EVIL.example.com./").is_some());
+        assert!(blocklist.find("https://notevil.example.com/").is_none());
+        assert_eq!(
+            blocklist
+                .find("https://shop.example.org/wp-admin/x")
+                .map(|r| r.code()),
+            Some("blocked_pattern")
+        );
+        assert!(blocklist.find("https://example.org/").is_none());
+    }
+
+    #[tokio::test]
+    async fn blocklist_reloads_when_file_changes() {
+        let path = env::temp_dir().join(format!("blocklist-{}.txt", std::process::id()));
+        fs::write(&path, "a.example.com\n").unwrap();
+        let check = BlocklistCheck::new(&path, Duration::from_millis(10));
+        assert!(check.check("https://a.example.com/").await.is_err());
+        assert!(check.check("https://b.example.com/").await.is_ok());
+
+        // 일부 파일 시스템은 수정 시각 단위가 1초
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        fs::write(&path, "b.example.com\n").unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(check.check("https://a.example.com/").await.is_ok());
+        assert!(check.check("https://b.example.com/").await.is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
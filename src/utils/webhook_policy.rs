@@ -0,0 +1,257 @@
+use crate::utils::fetcher::is_public_ip;
+use ipnet::IpNet;
+use reqwest::Url;
+use std::env;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+
+// 웹훅 목적지 정책
+// 예: WEBHOOK_ALLOW=hooks.example.com,*.partner.com,10.20.0.0/16
+//     WEBHOOK_DENY=evil.example.com,203.0.113.0/24
+//     WEBHOOK_ALLOW_PRIVATE=false, WEBHOOK_HTTPS_ONLY=true
+pub struct WebhookPolicy {
+    allow: Rules,
+    deny: Rules,
+    // 사설/루프백/링크 로컬 주소 허용 여부 (기본값 false, WEBHOOK_ALLOW 에 적은 대역은 항상 허용)
+    allow_private: bool,
+    https_only: bool,
+}
+
+// 호스트 이름 ("*.example.com" 은 하위 도메인) 과 IP 대역 목록
+#[derive(Default)]
+struct Rules {
+    hosts: Vec<String>,
+    nets: Vec<IpNet>,
+}
+
+impl Rules {
+    fn from_env(key: &str) -> Self {
+        Rules::parse(&env::var(key).unwrap_or("".to_string()))
+    }
+
+    // 쉼표로 구분한 목록, IP 나 CIDR 가 아니면 호스트 이름으로 취급
+    fn parse(value: &str) -> Self {
+        let mut rules = Rules::default();
+        for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let net = entry
+                .parse::<IpNet>()
+                .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from));
+            match net {
+                Ok(net) => rules.nets.push(net),
+                Err(_) => rules.hosts.push(entry.to_lowercase()),
+            }
+        }
+        rules
+    }
+
+    fn is_empty(&self) -> bool {
+        self.hosts.is_empty() && self.nets.is_empty()
+    }
+
+    fn matches_host(&self, host: &str) -> bool {
+        self.hosts.iter().any(|rule| match rule.strip_prefix("*.") {
+            Some(domain) => host.ends_with(&format!(".{}", domain)),
+            None => rule == host,
+        })
+    }
+
+    fn matches_ip(&self, ip: IpAddr) -> bool {
+        self.nets.iter().any(|net| net.contains(&ip))
+    }
+}
+
+#[derive(Debug)]
+pub enum PolicyError {
+    InvalidUrl,
+    HttpsRequired,
+    HostDenied(String),
+    HostNotAllowed(String),
+    AddressBlocked(IpAddr),
+    Resolve(String),
+}
+
+impl PolicyError {
+    // DNS 조회 실패는 일시적일 수 있으므로 재시도 대상
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, PolicyError::Resolve(_))
+    }
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyError::InvalidUrl => write!(f, "웹훅 URL 형태가 올바르지 않습니다."),
+            PolicyError::HttpsRequired => write!(f, "웹훅 URL 은 https 만 사용할 수 있습니다."),
+            PolicyError::HostDenied(host) => write!(f, "차단된 웹훅 호스트입니다: {}", host),
+            PolicyError::HostNotAllowed(host) => {
+                write!(f, "허용되지 않은 웹훅 호스트입니다: {}", host)
+            }
+            PolicyError::AddressBlocked(ip) => {
+                write!(f, "웹훅으로 보낼 수 없는 주소입니다: {}", ip)
+            }
+            PolicyError::Resolve(host) => write!(f, "웹훅 호스트를 찾을 수 없습니다: {}", host),
+        }
+    }
+}
+
+impl std::error::Error for PolicyError {}
+
+impl WebhookPolicy {
+    pub fn from_env() -> Self {
+        let flag = |key: &str| env::var(key).map(|v| v == "true").unwrap_or(false);
+        WebhookPolicy {
+            allow: Rules::from_env("WEBHOOK_ALLOW"),
+            deny: Rules::from_env("WEBHOOK_DENY"),
+            allow_private: flag("WEBHOOK_ALLOW_PRIVATE"),
+            https_only: flag("WEBHOOK_HTTPS_ONLY"),
+        }
+    }
+
+    // 정책을 통과하면 연결해도 되는 주소 반환
+    // 전송할 때는 이 주소로만 연결해야 검사 이후 DNS 응답이 바뀌어도 우회되지 않음
+    pub async fn check(&self, url: &str) -> Result<Vec<SocketAddr>, PolicyError> {
+        let url = Url::parse(url).map_err(|_| PolicyError::InvalidUrl)?;
+        match url.scheme() {
+            "https" => {}
+            "http" if !self.https_only => {}
+            "http" => return Err(PolicyError::HttpsRequired),
+            _ => return Err(PolicyError::InvalidUrl),
+        }
+        let host = url
+            .host_str()
+            .ok_or(PolicyError::InvalidUrl)?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_lowercase();
+        let port = url.port_or_known_default().ok_or(PolicyError::InvalidUrl)?;
+        if self.deny.matches_host(&host) {
+            return Err(PolicyError::HostDenied(host));
+        }
+
+        let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), port))
+            .await
+            .map_err(|_| PolicyError::Resolve(host.clone()))?
+            .collect();
+        if addrs.is_empty() {
+            return Err(PolicyError::Resolve(host));
+        }
+        let host_allowed = self.allow.matches_host(&host);
+        for addr in &addrs {
+            let ip = addr.ip();
+            if self.deny.matches_ip(ip) {
+                return Err(PolicyError::AddressBlocked(ip));
+            }
+            let ip_allowed = self.allow.matches_ip(ip);
+            // 허용 목록이 있으면 호스트 이름이나 주소 중 하나는 목록에 있어야 함
+            if !self.allow.is_empty() && !host_allowed && !ip_allowed {
+                return Err(PolicyError::HostNotAllowed(host));
+            }
+            if !is_public_ip(ip) && !self.allow_private && !ip_allowed {
+                return Err(PolicyError::AddressBlocked(ip));
+            }
+        }
+        Ok(addrs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(allow: &str, deny: &str) -> WebhookPolicy {
+        WebhookPolicy {
+            allow: Rules::parse(allow),
+            deny: Rules::parse(deny),
+            allow_private: false,
+            https_only: false,
+        }
+    }
+
+    // IP 리터럴은 DNS 조회 없이 그대로 주소가 됨
+    #[tokio::test]
+    async fn private_addresses_are_rejected_by_default() {
+        let policy = policy("", "");
+        for url in [
+            "http://127.0.0.1/hook",
+            "http://[::1]/hook",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://10.0.0.5/hook",
+            "http://172.16.0.1/hook",
+            "http://192.168.1.10:8080/hook",
+            "http://0.0.0.0/hook",
+        ] {
+            assert!(
+                matches!(policy.check(url).await, Err(PolicyError::AddressBlocked(_))),
+                "{}",
+                url
+            );
+        }
+        let addrs = policy.check("https://8.8.8.8/hook").await.unwrap();
+        assert_eq!(addrs, vec!["8.8.8.8:443".parse().unwrap()]);
+        assert!(matches!(
+            policy.check("ftp://8.8.8.8/hook").await,
+            Err(PolicyError::InvalidUrl)
+        ));
+    }
+
+    #[tokio::test]
+    async fn allow_list_overrides_private_ranges() {
+        let policy = policy("10.20.0.0/16, 203.0.113.7", "");
+        assert!(policy.check("http://10.20.1.2/hook").await.is_ok());
+        assert!(policy.check("http://203.0.113.7/hook").await.is_ok());
+        // 허용 목록이 있으면 목록 밖의 공인 주소도 거부
+        assert!(matches!(
+            policy.check("http://8.8.8.8/hook").await,
+            Err(PolicyError::HostNotAllowed(_))
+        ));
+        // 허용 대역 밖의 사설 주소
+        assert!(policy.check("http://10.21.0.1/hook").await.is_err());
+
+        let mut policy = policy;
+        policy.allow_private = true;
+        policy.allow = Rules::default();
+        assert!(policy.check("http://192.168.1.10/hook").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn deny_list_wins_over_allow_list() {
+        let policy = policy("203.0.113.0/24", "203.0.113.66, evil.example.com");
+        assert!(policy.check("http://203.0.113.7/hook").await.is_ok());
+        assert!(matches!(
+            policy.check("http://203.0.113.66/hook").await,
+            Err(PolicyError::AddressBlocked(_))
+        ));
+        // 호스트 이름 차단은 DNS 조회 전에 확인
+        assert!(matches!(
+            policy.check("https://EVIL.example.com/hook").await,
+            Err(PolicyError::HostDenied(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn https_only_rejects_http() {
+        let mut policy = policy("", "");
+        policy.https_only = true;
+        assert!(matches!(
+            policy.check("http://8.8.8.8/hook").await,
+            Err(PolicyError::HttpsRequired)
+        ));
+        assert!(policy.check("https://8.8.8.8/hook").await.is_ok());
+    }
+
+    #[test]
+    fn wildcard_matches_subdomains_only() {
+        let rules = Rules::parse("*.partner.com, hooks.example.com, 10.0.0.0/8, 192.0.2.1");
+        assert_eq!(rules.hosts, vec!["*.partner.com", "hooks.example.com"]);
+        assert_eq!(rules.nets.len(), 2);
+        assert!(rules.matches_host("a.partner.com"));
+        assert!(rules.matches_host("a.b.partner.com"));
+        assert!(!rules.matches_host("partner.com"));
+        assert!(!rules.matches_host("evilpartner.com"));
+        assert!(rules.matches_host("hooks.example.com"));
+        assert!(!rules.matches_host("a.hooks.example.com"));
+        assert!(rules.matches_ip("10.1.2.3".parse().unwrap()));
+        assert!(rules.matches_ip("192.0.2.1".parse().unwrap()));
+        assert!(!rules.matches_ip("192.0.2.2".parse().unwrap()));
+    }
+}
@@ -0,0 +1,41 @@
+use serde::Serialize;
+
+// 필드 하나의 유효성 검사 오류
+#[derive(Debug, Serialize)]
+pub struct FieldError {
+    // API 요청의 필드 이름 (예: iosDeepLink, openGraph.image)
+    pub field: &'static str,
+    pub message: String,
+}
+
+// 첫 오류에서 멈추지 않고 모든 필드의 오류를 모아서 한 번에 반환
+#[derive(Debug, Default)]
+pub struct FieldErrors(Vec<FieldError>);
+
+impl FieldErrors {
+    pub fn new() -> Self {
+        FieldErrors::default()
+    }
+
+    pub fn add(&mut self, field: &'static str, message: impl Into<String>) {
+        self.0.push(FieldError {
+            field,
+            message: message.into(),
+        });
+    }
+
+    // 검사 결과가 오류면 해당 필드의 오류로 추가
+    pub fn check(&mut self, field: &'static str, result: Result<(), String>) {
+        if let Err(message) = result {
+            self.add(field, message);
+        }
+    }
+
+    pub fn into_result(self) -> Result<(), Vec<FieldError>> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(self.0)
+        }
+    }
+}
@@ -1 +1,2 @@
+pub mod field_errors;
 pub mod validate_url;
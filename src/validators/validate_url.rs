@@ -1,20 +1,85 @@
+use crate::validators::field_errors::FieldErrors;
+use chrono::{DateTime, Utc};
+use email_address::{EmailAddress, Options};
 use regex::Regex;
+use reqwest::Url;
+use std::sync::LazyLock;
 
+pub const MAX_URL_LENGTH: usize = 2048;
+// RFC 5321 의 주소 최대 길이
+pub const MAX_EMAIL_LENGTH: usize = 254;
+pub const MAX_HEAD_HTML_LENGTH: usize = 64 * 1024;
+
+// 딥링크로 쓰거나 리다이렉트 페이지에서 이동하면 안 되는 scheme (브라우저에서 스크립트를 실행하거나 로컬 자원에 접근할 수 있음)
+pub const BLOCKED_SCHEMES: &[&str] = &["javascript", "vbscript", "data", "file", "blob", "about"];
+
+// RFC 5322 주소 형식 (표시 이름 "Name <a@b.com>" 과 IP 도메인 "a@[127.0.0.1]" 은 제외)
 pub fn validate_email(email: &str) -> Result<(), String> {
     if email.is_empty() {
         return Err("이메일이 없습니다.".to_string());
     }
+    if email.len() > MAX_EMAIL_LENGTH {
+        return Err(format!("이메일은 {}자 이하여야 합니다.", MAX_EMAIL_LENGTH));
+    }
+    let options = Options::default()
+        .without_display_text()
+        .without_domain_literal();
+    if EmailAddress::parse_with_options(email, options).is_err() {
+        return Err("이메일 형태가 올바르지 않습니다.".to_string());
+    }
     Ok(())
 }
 
+// 길이와 문자를 검사한 뒤 파싱
+// Url::parse 는 앞뒤 공백과 중간의 탭, 줄바꿈을 조용히 제거하므로 먼저 거부
+fn parse_url(url: &str) -> Result<Url, String> {
+    if url.len() > MAX_URL_LENGTH {
+        return Err(format!("URL 은 {}자 이하여야 합니다.", MAX_URL_LENGTH));
+    }
+    if url.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err("URL 에 공백이나 제어 문자를 넣을 수 없습니다.".to_string());
+    }
+    Url::parse(url).map_err(|_| "URL 형태가 올바르지 않습니다.".to_string())
+}
+
+// 호스트가 있는 http(s) URL
 pub fn validate_url(url: &str) -> Result<(), String> {
-    let url_regex = Regex::new(r"^https?://[^\s/$.?#].[^\s]*$").unwrap();
-    if !url_regex.is_match(url) {
+    let parsed = parse_url(url)?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
         return Err("URL 형태가 올바르지 않습니다.".to_string());
     }
     Ok(())
 }
 
+// 앱 딥링크 (빈 값은 지정하지 않음): myapp://product/1 같은 앱 scheme, intent:// 또는 http(s) URL
+pub fn validate_deep_link(deep_link: &str) -> Result<(), String> {
+    if deep_link.is_empty() {
+        return Ok(());
+    }
+    let parsed = parse_url(deep_link)?;
+    let scheme = parsed.scheme();
+    if BLOCKED_SCHEMES.contains(&scheme) {
+        return Err(format!(
+            "딥링크에 {}: scheme 은 사용할 수 없습니다.",
+            scheme
+        ));
+    }
+    if matches!(scheme, "http" | "https") {
+        validate_url(deep_link)?;
+    }
+    Ok(())
+}
+
+pub fn validate_head_html(head_html: &str) -> Result<(), String> {
+    if head_html.len() > MAX_HEAD_HTML_LENGTH {
+        return Err(format!(
+            "headHtml 은 {}바이트 이하여야 합니다.",
+            MAX_HEAD_HTML_LENGTH
+        ));
+    }
+    Ok(())
+}
+
 pub fn validate_webhook_url(url: &str) -> Result<(), String> {
     if !url.is_empty() {
         validate_url(url)?;
@@ -28,3 +93,310 @@ pub fn validate_fallback_url(url: &str) -> Result<(), String> {
     }
     Ok(())
 }
+
+pub fn validate_redirect_status(status: i32) -> Result<(), String> {
+    if ![301, 302, 307, 308].contains(&status) {
+        return Err("리다이렉트 상태 코드는 301, 302, 307, 308 중 하나여야 합니다.".to_string());
+    }
+    Ok(())
+}
+
+pub const EXPIRED_ACTIONS: [&str; 3] = ["gone", "page", "redirect"];
+
+// 접속 가능 기간, 최대 접속 횟수, 만료 시 동작 검사
+pub fn validate_link_limits(
+    errors: &mut FieldErrors,
+    activates_at: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+    max_clicks: Option<i32>,
+    expired_action: &str,
+    expired_fallback_url: &str,
+) {
+    if let (Some(activates_at), Some(expires_at)) = (activates_at, expires_at) {
+        if activates_at >= expires_at {
+            errors.add("expiresAt", "만료 시각은 활성화 시각 이후여야 합니다.");
+        }
+    }
+    if max_clicks.is_some_and(|max_clicks| max_clicks < 1) {
+        errors.add("maxClicks", "최대 접속 횟수는 1 이상이어야 합니다.");
+    }
+    if !EXPIRED_ACTIONS.contains(&expired_action) {
+        errors.add(
+            "expiredAction",
+            "만료 시 동작은 gone, page, redirect 중 하나여야 합니다.",
+        );
+    }
+    if expired_action == "redirect" {
+        errors.check("expiredFallbackUrl", validate_url(expired_fallback_url));
+    } else {
+        errors.check(
+            "expiredFallbackUrl",
+            validate_fallback_url(expired_fallback_url),
+        );
+    }
+}
+
+// 새로 지정하는 만료 시각은 현재 이후여야 함 (지정하자마자 만료되는 링크 방지)
+pub fn validate_expires_at(
+    expires_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Result<(), String> {
+    if expires_at.is_some_and(|expires_at| expires_at <= now) {
+        return Err("만료 시각은 현재 이후여야 합니다.".to_string());
+    }
+    Ok(())
+}
+
+// 영구 리다이렉트는 브라우저가 캐시해 이후 접속이 서버를 거치지 않으므로 제한과 함께 쓸 수 없음
+pub fn validate_permanent_redirect(
+    redirect_status: i32,
+    activates_at: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+    max_clicks: Option<i32>,
+    password_protected: bool,
+) -> Result<(), String> {
+    let has_limits = activates_at.is_some()
+        || expires_at.is_some()
+        || max_clicks.is_some()
+        || password_protected;
+    if has_limits && [301, 308].contains(&redirect_status) {
+        return Err(
+            "활성화/만료 시각, 최대 접속 횟수나 비밀번호가 있으면 301, 308 리다이렉트를 사용할 수 없습니다."
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+// 리다이렉트 페이지의 스크립트로 이동해도 안전한 링크인지 (빈 값은 안전)
+// 검사 도입 이전에 저장된 값도 있으므로 렌더링할 때 다시 확인
+pub fn is_safe_link(link: &str) -> bool {
+    if link.is_empty() {
+        return true;
+    }
+    // 브라우저와 같은 규칙으로 scheme 을 해석 (앞쪽 공백, 중간의 탭/줄바꿈 무시)
+    Url::parse(link).is_ok_and(|url| !BLOCKED_SCHEMES.contains(&url.scheme()))
+}
+
+// 링크 비밀번호 (빈 값은 비밀번호 없음)
+pub fn validate_password(password: &str) -> Result<(), String> {
+    let length = password.chars().count();
+    if length > 0 && !(4..=128).contains(&length) {
+        return Err("비밀번호는 4자 이상 128자 이하여야 합니다.".to_string());
+    }
+    Ok(())
+}
+
+// 별칭으로 쓸 수 없는 단어 (기존 경로와 겹치거나 서비스 페이지로 오해할 수 있는 이름)
+pub const RESERVED_ALIASES: &[&str] = &[
+    "admin",
+    "api",
+    "apple-app-site-association",
+    "assets",
+    "favicon",
+    "health",
+    "index",
+    "login",
+    "logout",
+    "robots",
+    "static",
+    "v1",
+    "v2",
+    "verify",
+    "www",
+];
+
+// 별칭: 영문 소문자, 숫자, '-', '_' 로 3~64자이고 영문 소문자나 숫자로 시작하고 끝남
+// 접속할 때마다 검사하므로 정규식은 한 번만 컴파일
+static ALIAS_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[a-z0-9][a-z0-9_-]{1,62}[a-z0-9]$").unwrap());
+
+pub fn validate_alias(alias: &str) -> Result<(), String> {
+    if !ALIAS_REGEX.is_match(alias) {
+        return Err(
+            "별칭은 영문 소문자, 숫자, '-', '_' 로 된 3자 이상 64자 이하여야 합니다.".to_string(),
+        );
+    }
+    if RESERVED_ALIASES.contains(&alias) {
+        return Err("사용할 수 없는 별칭입니다.".to_string());
+    }
+    Ok(())
+}
+
+// Android 패키지 이름 (빈 값은 지정하지 않음): 점으로 구분된 두 단계 이상, 각 단계는 영문자로 시작
+static PACKAGE_NAME_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[a-zA-Z][a-zA-Z0-9_]*(\.[a-zA-Z][a-zA-Z0-9_]*)+$").unwrap());
+
+pub fn validate_android_package_name(package_name: &str) -> Result<(), String> {
+    if !package_name.is_empty() && !PACKAGE_NAME_REGEX.is_match(package_name) {
+        return Err("Android 패키지 이름 형태가 올바르지 않습니다.".to_string());
+    }
+    Ok(())
+}
+
+pub const TWITTER_CARDS: [&str; 4] = ["summary", "summary_large_image", "app", "player"];
+
+// 미리보기용 메타데이터 (빈 값은 지정하지 않음)
+pub fn validate_open_graph(
+    errors: &mut FieldErrors,
+    title: &str,
+    description: &str,
+    image: &str,
+    twitter_card: &str,
+) {
+    if title.chars().count() > 200 {
+        errors.add("openGraph.title", "미리보기 제목은 200자 이하여야 합니다.");
+    }
+    if description.chars().count() > 500 {
+        errors.add(
+            "openGraph.description",
+            "미리보기 설명은 500자 이하여야 합니다.",
+        );
+    }
+    errors.check("openGraph.image", validate_fallback_url(image));
+    if !twitter_card.is_empty() && !TWITTER_CARDS.contains(&twitter_card) {
+        errors.add(
+            "openGraph.twitterCard",
+            "twitterCard 는 summary, summary_large_image, app, player 중 하나여야 합니다.",
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deep_links_allow_app_schemes_only() {
+        for link in [
+            "",
+            "myapp://product/1",
+            "fb123://profile",
+            "intent://scan/#Intent;scheme=zxing;package=com.google.zxing.client.android;end",
+            "https://example.com/app",
+        ] {
+            assert!(validate_deep_link(link).is_ok(), "{}", link);
+        }
+        for link in [
+            "javascript:alert(1)",
+            "JavaScript:alert(1)",
+            "data:text/html,<script>alert(1)</script>",
+            "vbscript:msgbox",
+            "file:///etc/passwd",
+            "myapp://product/1 x",
+            "java\tscript:alert(1)",
+            "not a link",
+            "https://",
+        ] {
+            assert!(validate_deep_link(link).is_err(), "{}", link);
+        }
+    }
+
+    #[test]
+    fn urls_must_be_http_with_host() {
+        assert!(validate_url("https://example.com/a?b=c").is_ok());
+        assert!(validate_url("ftp://example.com/").is_err());
+        assert!(validate_url(" https://example.com/").is_err());
+        assert!(validate_url(&format!(
+            "https://example.com/{}",
+            "a".repeat(MAX_URL_LENGTH)
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn emails_follow_rfc_5322() {
+        for email in [
+            "a@b.com",
+            "first.last+tag@example.co.kr",
+            "\"quoted name\"@example.com",
+        ] {
+            assert!(validate_email(email).is_ok(), "{}", email);
+        }
+        for email in [
+            "",
+            "plain",
+            "a@",
+            "@b.com",
+            "a@@b.com",
+            "Name <a@b.com>",
+            "a@[127.0.0.1]",
+        ] {
+            assert!(validate_email(email).is_err(), "{}", email);
+        }
+    }
+
+    #[test]
+    fn unsafe_links_are_refused_at_render_time() {
+        for link in ["", "myapp://item/1", "https://example.com/"] {
+            assert!(is_safe_link(link), "{}", link);
+        }
+        for link in [
+            "javascript:alert(1)",
+            " JavaScript:alert(1)",
+            "java\tscript:alert(1)",
+            "data:text/html,<script>alert(1)</script>",
+            "not a link",
+        ] {
+            assert!(!is_safe_link(link), "{}", link);
+        }
+    }
+
+    #[test]
+    fn permanent_redirects_cannot_have_limits() {
+        let expires_at = Some(Utc::now() + chrono::Duration::days(1));
+        for status in [301, 308] {
+            assert!(validate_permanent_redirect(status, None, None, None, false).is_ok());
+            assert!(validate_permanent_redirect(status, None, expires_at, None, false).is_err());
+            assert!(validate_permanent_redirect(status, expires_at, None, None, false).is_err());
+            assert!(validate_permanent_redirect(status, None, None, Some(3), false).is_err());
+            assert!(validate_permanent_redirect(status, None, None, None, true).is_err());
+        }
+        for status in [302, 307] {
+            assert!(validate_permanent_redirect(status, None, expires_at, Some(3), true).is_ok());
+        }
+    }
+
+    #[test]
+    fn new_expiry_must_be_in_the_future() {
+        let now = Utc::now();
+        assert!(validate_expires_at(None, now).is_ok());
+        assert!(validate_expires_at(Some(now + chrono::Duration::seconds(1)), now).is_ok());
+        assert!(validate_expires_at(Some(now), now).is_err());
+        assert!(validate_expires_at(Some(now - chrono::Duration::days(1)), now).is_err());
+    }
+
+    #[test]
+    fn aliases_must_not_be_reserved() {
+        for alias in RESERVED_ALIASES {
+            assert!(validate_alias(alias).is_err(), "{}", alias);
+        }
+        assert!(validate_alias("verify-me").is_ok());
+    }
+
+    #[test]
+    fn aliases_must_be_3_to_64_characters() {
+        assert!(validate_alias("ab").is_err());
+        assert!(validate_alias("abc").is_ok());
+        assert!(validate_alias(&"a".repeat(64)).is_ok());
+        assert!(validate_alias(&"a".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn aliases_use_lowercase_digits_dash_and_underscore() {
+        for alias in ["spring-sale", "sale_2024", "2024"] {
+            assert!(validate_alias(alias).is_ok(), "{}", alias);
+        }
+        for alias in [
+            "Spring-Sale",
+            "-sale",
+            "sale_",
+            "spring sale",
+            "sale/2024",
+            "세일-행사",
+            "sale\n",
+        ] {
+            assert!(validate_alias(alias).is_err(), "{}", alias);
+        }
+    }
+}
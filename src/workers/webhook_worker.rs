@@ -0,0 +1,190 @@
+use crate::models::repository::{DbError, WebhookDelivery};
+use crate::state::AppState;
+use crate::utils::signature::{sign_webhook, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+use chrono::Utc;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::redirect::Policy;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinSet;
+
+// 백오프 간격 상한
+const MAX_BACKOFF: Duration = Duration::from_secs(3600);
+
+#[derive(Clone)]
+pub struct WebhookConfig {
+    pub max_attempts: i32,
+    pub timeout: Duration,
+    pub backoff_base: Duration,
+    pub poll_interval: Duration,
+    pub batch_size: i64,
+}
+
+impl WebhookConfig {
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(key: &str, default: T) -> T {
+            env::var(key)
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(default)
+        }
+        WebhookConfig {
+            max_attempts: var("WEBHOOK_MAX_ATTEMPTS", 8),
+            timeout: Duration::from_secs(var("WEBHOOK_TIMEOUT_SECONDS", 10)),
+            backoff_base: Duration::from_secs(var("WEBHOOK_BACKOFF_SECONDS", 10)),
+            poll_interval: Duration::from_secs(var("WEBHOOK_POLL_INTERVAL_SECONDS", 5)),
+            batch_size: var("WEBHOOK_BATCH_SIZE", 50),
+        }
+    }
+
+    // n 번째 실패 후 다음 시도까지의 대기 시간 (base * 2^(n-1), 최대 1시간)
+    fn backoff(&self, attempts: i32) -> Duration {
+        let exponent = (attempts - 1).clamp(0, 16) as u32;
+        self.backoff_base
+            .saturating_mul(2u32.pow(exponent))
+            .min(MAX_BACKOFF)
+    }
+}
+
+// 웹훅 전송 워커
+// 대기열에서 전송 시각이 된 건을 가져와 전송하고, 실패하면 지수 백오프로 재시도 예약
+pub async fn run_webhook_worker(state: Arc<AppState>, config: WebhookConfig) {
+    loop {
+        let now = Utc::now();
+        // 전송 중 프로세스가 종료되어도 임대 시간이 지나면 다시 전송되도록 함
+        let lease_until = now
+            + chrono::Duration::from_std(config.timeout * 2)
+                .unwrap_or(chrono::Duration::minutes(1));
+        match state
+            .webhooks
+            .claim_due(now, lease_until, config.batch_size)
+            .await
+        {
+            Ok(deliveries) if !deliveries.is_empty() => {
+                let mut tasks = JoinSet::new();
+                for delivery in deliveries {
+                    tasks.spawn(deliver(state.clone(), config.clone(), delivery));
+                }
+                while tasks.join_next().await.is_some() {}
+                continue;
+            }
+            Ok(_) => {}
+            Err(e) => println!("웹훅 대기열 조회 실패: {}", e),
+        }
+        // 새 건이 들어오면 바로 깨어나고, 아니면 주기적으로 재시도 대상 확인
+        let _ = tokio::time::timeout(config.poll_interval, state.webhook_notify.notified()).await;
+    }
+}
+
+async fn deliver(state: Arc<AppState>, config: WebhookConfig, delivery: WebhookDelivery) {
+    let attempts = delivery.attempts + 1;
+    // 전송 직전에 정책을 다시 검사하고, 검사한 주소로만 연결 (DNS 재바인딩 방지)
+    // 리다이렉트를 따라가면 정책을 우회할 수 있으므로 따라가지 않음
+    let client = match state.webhook_policy.check(&delivery.webhook_url).await {
+        Ok(addrs) => {
+            let host = reqwest::Url::parse(&delivery.webhook_url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_string))
+                .unwrap_or_default();
+            reqwest::Client::builder()
+                .timeout(config.timeout)
+                .redirect(Policy::none())
+                .resolve_to_addrs(&host, &addrs)
+                .build()
+        }
+        Err(e) if e.is_retryable() && attempts < config.max_attempts => {
+            let next_attempt_at = Utc::now()
+                + chrono::Duration::from_std(config.backoff(attempts))
+                    .unwrap_or(chrono::Duration::hours(1));
+            let saved = state
+                .webhooks
+                .mark_retry(delivery.id, attempts, next_attempt_at, &e.to_string())
+                .await;
+            if let Err(e) = saved {
+                println!("웹훅 전송 결과 저장 실패 ({}): {}", delivery.id, e);
+            }
+            return;
+        }
+        Err(e) => {
+            println!("웹훅 목적지 정책 위반 ({}): {}", delivery.id, e);
+            let saved = state
+                .webhooks
+                .mark_failed(delivery.id, attempts, &e.to_string())
+                .await;
+            if let Err(e) = saved {
+                println!("웹훅 전송 결과 저장 실패 ({}): {}", delivery.id, e);
+            }
+            return;
+        }
+    };
+    let client = match client {
+        Ok(client) => client,
+        Err(e) => {
+            println!("웹훅 클라이언트 생성 실패 ({}): {}", delivery.id, e);
+            return;
+        }
+    };
+    let mut request = client
+        .post(&delivery.webhook_url)
+        .header(CONTENT_TYPE, "application/json");
+    // 재시도마다 현재 시각으로 다시 서명 (수신 측의 타임스탬프 허용 범위 검사 통과용)
+    match state.urls.find_webhook_secret(delivery.url_id).await {
+        Ok(Some(secret)) => {
+            let timestamp = Utc::now().timestamp();
+            request = request.header(TIMESTAMP_HEADER, timestamp).header(
+                SIGNATURE_HEADER,
+                sign_webhook(&secret, timestamp, &delivery.payload),
+            );
+        }
+        // 서명 키 도입 이전에 만든 URL 은 서명 없이 전송
+        Ok(None) => {}
+        // 조회 실패도 전송 실패와 같이 백오프 후 재시도 (대기열에 임대 상태로 남지 않도록)
+        Err(e) => {
+            println!("웹훅 서명 키 조회 실패 ({}): {}", delivery.id, e);
+            let error = format!("서명 키 조회 실패: {}", e);
+            if let Err(e) = record_failure(&state, &config, &delivery, attempts, &error).await {
+                println!("웹훅 전송 결과 저장 실패 ({}): {}", delivery.id, e);
+            }
+            return;
+        }
+    }
+    let result = request
+        .body(delivery.payload.clone())
+        .send()
+        .await
+        .and_then(|response| response.error_for_status());
+
+    let saved = match result {
+        Ok(_) => state.webhooks.mark_delivered(delivery.id, attempts).await,
+        Err(e) => record_failure(&state, &config, &delivery, attempts, &e.to_string()).await,
+    };
+    if let Err(e) = saved {
+        println!("웹훅 전송 결과 저장 실패 ({}): {}", delivery.id, e);
+    }
+}
+
+// 전송 실패 기록
+// 최대 시도 횟수에 도달하면 최종 실패, 아니면 지수 백오프 후 재시도 예약
+async fn record_failure(
+    state: &AppState,
+    config: &WebhookConfig,
+    delivery: &WebhookDelivery,
+    attempts: i32,
+    error: &str,
+) -> Result<(), DbError> {
+    if attempts >= config.max_attempts {
+        println!("웹훅 전송 최종 실패 ({}): {}", delivery.id, error);
+        return state
+            .webhooks
+            .mark_failed(delivery.id, attempts, error)
+            .await;
+    }
+    let next_attempt_at = Utc::now()
+        + chrono::Duration::from_std(config.backoff(attempts))
+            .unwrap_or(chrono::Duration::hours(1));
+    state
+        .webhooks
+        .mark_retry(delivery.id, attempts, next_attempt_at, error)
+        .await
+}